@@ -0,0 +1,100 @@
+//! Time from the ban click to the PATCH reaching a local mock LCU, for a client built on every ban
+//! (lockfile read, TLS handshake, session and bannable champions requests) against a connected
+//! client with the ban prepared from the last session update.
+//!
+//! `cargo run --release --features mock-lcu --example ban_latency`
+
+use std::time::{Duration, Instant};
+
+use ban_ahri_client::{AHRI_ID, AppResult};
+use ban_ahri_client::client::{ban_champion_list, execute_ban, lockfile_path, LolClient, prepare_ban, set_accept_invalid_certs};
+use ban_ahri_client::client::champ_select_session::ChampSelectSession;
+use ban_ahri_client::client::gameflow::GameflowPhase;
+use ban_ahri_client::mock_lcu::{MockLcu, MockState};
+
+const ITERATIONS: usize = 20;
+const BAN_ACTION_ID: i64 = 1;
+
+/// Ban phase with the local player's ban in progress.
+fn ban_turn_session() -> ChampSelectSession {
+    serde_json::from_value(serde_json::json!({
+        "actions": [[
+            {"actorCellId": 0, "championId": 0, "completed": false, "id": BAN_ACTION_ID, "isAllyAction": true, "isInProgress": true, "pickTurn": 1, "type": "ban"},
+            {"actorCellId": 5, "championId": 0, "completed": false, "id": 2, "isAllyAction": false, "isInProgress": true, "pickTurn": 1, "type": "ban"}
+        ]],
+        "bans": {"myTeamBans": [], "numBans": 10, "theirTeamBans": []},
+        "gameId": 1,
+        "localPlayerCellId": 0,
+        "timer": {"adjustedTimeLeftInPhase": 30000, "internalNowInEpochMs": 0, "isInfinite": false, "phase": "BAN_PICK", "totalTimeInPhase": 30000}
+    })).expect("valid session")
+}
+
+/// Time between `start` and the last ban PATCH received by the mock.
+fn patch_latency(mock: &MockLcu, start: Instant) -> Duration {
+    let state = mock.state();
+    let patch = state.requests
+        .iter()
+        .rev()
+        .find(|request| request.method == "PATCH")
+        .expect("ban request");
+    patch.received_at - start
+}
+
+fn reset(mock: &MockLcu) {
+    let mut state = mock.state();
+    state.session = Some(ban_turn_session());
+    state.requests.clear();
+}
+
+fn report(name: &str, mut latencies: Vec<Duration>) {
+    latencies.sort();
+    let percentile = |percent: usize| latencies[(latencies.len() - 1) * percent / 100].as_secs_f64() * 1000.0;
+    println!(
+        "{:<22} min {:>7.2}ms  median {:>7.2}ms  p95 {:>7.2}ms",
+        name,
+        percentile(0),
+        percentile(50),
+        percentile(95),
+    );
+}
+
+#[tokio::main]
+async fn main() -> AppResult<()> {
+    set_accept_invalid_certs(true);
+    let mock = MockLcu::start(0, MockState {
+        phase: GameflowPhase::ChampSelect,
+        session: Some(ban_turn_session()),
+        bannable_champions: vec![AHRI_ID],
+        ..Default::default()
+    }).await?;
+    let riot_dir = std::env::temp_dir().join(format!("ban_latency_{}", std::process::id()));
+    let riot_path = riot_dir.to_string_lossy().to_string();
+    std::fs::create_dir_all(riot_dir.join("League of Legends"))?;
+    std::fs::write(lockfile_path(riot_path.clone()), mock.lockfile().to_string())?;
+
+    let mut cold = Vec::new();
+    for _ in 0..ITERATIONS {
+        reset(&mock);
+        let start = Instant::now();
+        ban_champion_list(riot_path.clone(), &[AHRI_ID]).await?;
+        cold.push(patch_latency(&mock, start));
+    }
+
+    let client = LolClient::from_lockfile(&mock.lockfile())?;
+    client.warm_up().await?;
+    let mut warm = Vec::new();
+    for _ in 0..ITERATIONS {
+        reset(&mock);
+        // done on each session update, before the click
+        let prepared_ban = prepare_ban(&ban_turn_session(), &[AHRI_ID], &[AHRI_ID])?;
+        let start = Instant::now();
+        execute_ban(&client, prepared_ban).await?;
+        warm.push(patch_latency(&mock, start));
+    }
+
+    println!();
+    report("new client per ban", cold);
+    report("connected client", warm);
+    std::fs::remove_dir_all(riot_dir)?;
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,24 @@
+use std::process::ExitCode;
+
+use ban_ahri_client::cli::{Cli, USAGE};
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let result = match Cli::parse(std::env::args().skip(1)) {
+        Ok(cli) => cli.run().await,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            return ExitCode::from(err.exit_code());
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            match err.suggestion() {
+                Some(suggestion) => eprintln!("{} {}", err, suggestion),
+                None => eprintln!("{}", err),
+            }
+            ExitCode::from(err.exit_code())
+        }
+    }
+}
\ No newline at end of file
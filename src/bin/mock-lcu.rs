@@ -0,0 +1,111 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use ban_ahri_client::{AppError, AppResult};
+use ban_ahri_client::client::lockfile_path;
+use ban_ahri_client::mock_lcu::{DEFAULT_SCENARIO, MockLcu, Scenario};
+
+const USAGE: &str = "\
+Usage: mock-lcu [options]
+
+Serves a fake League client on 127.0.0.1 and plays a champion select scenario,
+with its lockfile written in <riot-path>/League of Legends.
+
+Options:
+      --port <port>          Port to listen on (default: any free one)
+      --scenario <file>      JSON scenario to play (default: assets/mock_lcu/scenario.json)
+      --riot-path <path>     Directory to write the lockfile in (default: <temp dir>/mock-lcu)
+      --repeat               Play the scenario again once finished, until interrupted
+  -h, --help                 Show this help";
+
+#[derive(Debug, Clone, PartialEq)]
+struct Options {
+    port: u16,
+    scenario: Option<PathBuf>,
+    riot_path: PathBuf,
+    repeat: bool,
+}
+
+
+impl Options {
+    /// `None` when the help is asked for.
+    fn parse(args: impl IntoIterator<Item=String>) -> AppResult<Option<Self>> {
+        let mut options = Self {
+            port: 0,
+            scenario: None,
+            riot_path: std::env::temp_dir().join("mock-lcu"),
+            repeat: false,
+        };
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| AppError::InvalidArgument(format!("{} expects a value", arg)));
+            match arg.as_str() {
+                "--port" => {
+                    let port = value()?;
+                    options.port = port.parse()
+                        .map_err(|_| AppError::InvalidArgument(format!("--port expects a port, got {:?}", port)))?;
+                }
+                "--scenario" => options.scenario = Some(PathBuf::from(value()?)),
+                "--riot-path" => options.riot_path = PathBuf::from(value()?),
+                "--repeat" => options.repeat = true,
+                "-h" | "--help" => return Ok(None),
+                _ => return Err(AppError::InvalidArgument(format!("unknown argument {:?}", arg))),
+            }
+        }
+        Ok(Some(options))
+    }
+
+    async fn run(self) -> AppResult<()> {
+        let scenario = match &self.scenario {
+            Some(path) => Scenario::read(path)?,
+            None => Scenario::parse(DEFAULT_SCENARIO)?,
+        };
+        let mock = MockLcu::start(self.port, scenario.initial_state()).await?;
+        let riot_path = self.riot_path.to_string_lossy().to_string();
+        let lockfile_path = lockfile_path(riot_path.clone());
+        std::fs::create_dir_all(self.riot_path.join("League of Legends"))?;
+        std::fs::write(&lockfile_path, mock.lockfile().to_string())?;
+        println!("Mock LCU listening on 127.0.0.1:{}", mock.port);
+        println!("Riot path: {}", riot_path);
+        println!("Its certificate is self-signed, set accept_invalid_certs = true in the config or pass --insecure to ban_ahri_cli");
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = async {
+                loop {
+                    mock.play(&scenario).await;
+                    if !self.repeat {
+                        println!("Scenario finished, press Ctrl+C to stop");
+                        std::future::pending::<()>().await;
+                    }
+                }
+            } => {}
+        }
+        // like the client, so the app sees it stopped
+        std::fs::remove_file(lockfile_path)?;
+        Ok(())
+    }
+}
+
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let result = match Options::parse(std::env::args().skip(1)) {
+        Ok(Some(options)) => options.run().await,
+        Ok(None) => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            return ExitCode::from(err.exit_code());
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::from(err.exit_code())
+        }
+    }
+}
\ No newline at end of file
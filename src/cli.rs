@@ -0,0 +1,305 @@
+use tokio::sync::mpsc;
+
+use crate::{AppError, AppResult, ChampionId, wait_n_millis};
+use crate::client::{ActionMode, auto_ban_champion_list, BAN_AHRI_THREAD_RETRY_MILLIS, BanOutcome, ban_champion_list, CHAMP_SELECT_SESSION_ENDPOINT, check_riot_path, find_lockfile, LolClient, set_accept_invalid_certs};
+use crate::client::champ_select_session::ChampSelectSession;
+use crate::client::champion_catalog::ChampionCatalog;
+use crate::client::discovery::discover_riot_paths;
+use crate::client::gameflow::GameflowPhase;
+use crate::client::pick::pick_thread;
+use crate::client::ready_check::ready_check_thread;
+use crate::client::schedule::BanTiming;
+use crate::config::Config;
+
+pub const USAGE: &str = "\
+Usage: ban_ahri_cli <command> [options]
+
+Commands:
+  ban       Ban (or hover during planning) the first available champion
+  watch     Ban automatically on every ban turn, until interrupted
+  status    Show the client connection and gameflow phase
+  session   Show the champion select session
+
+Options:
+  -c, --champion <name|id>   Champion to ban, repeat for fallbacks (default: the saved ban list)
+      --riot-path <path>     Riot Games installation directory (default: the saved one, or the detected one)
+      --insecure             Accept any client certificate instead of only the Riot one
+      --json                 session: print the raw session as JSON
+      --late                 watch: lock the ban at the end of the ban phase
+      --auto-accept          watch: accept ready checks
+      --accept-delay <ms>    watch: wait before answering ready checks
+      --decline-when-away    watch: decline ready checks while away
+      --auto-pick            watch: pick from the saved pick preferences
+  -h, --help                 Show this help";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliCommand {
+    Ban,
+    Watch,
+    Status,
+    Session,
+    Help,
+}
+
+
+/// Arguments of the headless binary, options not used by the command are ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cli {
+    pub command: CliCommand,
+    pub champions: Vec<String>,
+    /// The saved one when not given.
+    pub riot_path: Option<String>,
+    pub insecure: bool,
+    pub json: bool,
+    pub late: bool,
+    pub auto_accept: bool,
+    pub accept_delay_millis: Option<u64>,
+    pub decline_when_away: bool,
+    pub auto_pick: bool,
+}
+
+
+impl Cli {
+    pub fn parse(args: impl IntoIterator<Item=String>) -> AppResult<Self> {
+        let mut command = None;
+        let mut cli = Self {
+            command: CliCommand::Help,
+            champions: Vec::new(),
+            riot_path: None,
+            insecure: false,
+            json: false,
+            late: false,
+            auto_accept: false,
+            accept_delay_millis: None,
+            decline_when_away: false,
+            auto_pick: false,
+        };
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| AppError::InvalidArgument(format!("{} expects a value", arg)));
+            match arg.as_str() {
+                "-c" | "--champion" => cli.champions.push(value()?),
+                "--riot-path" => cli.riot_path = Some(value()?),
+                "--accept-delay" => {
+                    let delay = value()?;
+                    let delay = delay.parse()
+                        .map_err(|_| AppError::InvalidArgument(format!("--accept-delay expects milliseconds, got {:?}", delay)))?;
+                    cli.accept_delay_millis = Some(delay);
+                }
+                "--insecure" => cli.insecure = true,
+                "--json" => cli.json = true,
+                "--late" => cli.late = true,
+                "--auto-accept" => cli.auto_accept = true,
+                "--decline-when-away" => cli.decline_when_away = true,
+                "--auto-pick" => cli.auto_pick = true,
+                "-h" | "--help" => command = Some(CliCommand::Help),
+                _ if arg.starts_with('-') => return Err(AppError::InvalidArgument(format!("unknown option {}", arg))),
+                _ if command.is_some() => return Err(AppError::InvalidArgument(format!("unexpected argument {:?}", arg))),
+                "ban" => command = Some(CliCommand::Ban),
+                "watch" => command = Some(CliCommand::Watch),
+                "status" => command = Some(CliCommand::Status),
+                "session" => command = Some(CliCommand::Session),
+                _ => return Err(AppError::InvalidArgument(format!("unknown command {:?}", arg))),
+            }
+        }
+        cli.command = command.ok_or_else(|| AppError::InvalidArgument("missing command".to_string()))?;
+        Ok(cli)
+    }
+
+    pub async fn run(mut self) -> AppResult<()> {
+        let config = Config::load();
+        if self.riot_path.is_none() {
+            // fall back to the best detected installation when the saved one is gone
+            let riot_path = Some(config.riot_path.clone())
+                .filter(|riot_path| check_riot_path(riot_path.clone()))
+                .or_else(|| discover_riot_paths().into_iter().next().map(|candidate| candidate.path))
+                .unwrap_or_else(|| config.riot_path.clone());
+            self.riot_path = Some(riot_path);
+        }
+        set_accept_invalid_certs(self.insecure || config.accept_invalid_certs);
+        match self.command {
+            CliCommand::Ban => self.ban(&config).await,
+            CliCommand::Watch => self.watch(&config).await,
+            CliCommand::Status => self.status().await,
+            CliCommand::Session => self.session().await,
+            CliCommand::Help => {
+                println!("{}", USAGE);
+                Ok(())
+            }
+        }
+    }
+
+    fn riot_path(&self) -> String {
+        self.riot_path.clone().unwrap_or_default()
+    }
+
+    async fn ban(&self, config: &Config) -> AppResult<()> {
+        let (champion_ids, catalog) = self.resolve_champions(config).await?;
+        let outcome = ban_champion_list(self.riot_path(), &champion_ids).await?;
+        println!("{}", format_ban(&outcome, &catalog));
+        Ok(())
+    }
+
+    /// Bans on every ban turn and keeps the ready check and pick automations running alongside, until killed.
+    async fn watch(&self, config: &Config) -> AppResult<()> {
+        let (champion_ids, catalog) = self.resolve_champions(config).await?;
+        let timing = if self.late {
+            BanTiming::Late { lead_millis: config.ban.late_lead_millis }
+        } else {
+            config.ban_timing()
+        };
+        // the senders are kept until the end so the threads aren't stopped
+        let (_ready_check_sender, receiver) = mpsc::channel(1);
+        if self.auto_accept || config.ready_check.auto_accept {
+            let mut options = config.ready_check_options();
+            options.delay_millis = self.accept_delay_millis.unwrap_or(options.delay_millis);
+            options.decline_when_away |= self.decline_when_away;
+            tokio::spawn(ready_check_thread(self.riot_path(), options, receiver));
+        }
+        let (_pick_sender, receiver) = mpsc::channel(1);
+        if self.auto_pick || config.pick.auto_pick {
+            tokio::spawn(pick_thread(self.riot_path(), config.pick.preferences.clone(), receiver));
+        }
+        println!("Waiting for ban turns, press Ctrl+C to stop");
+        loop {
+            match auto_ban_champion_list(self.riot_path(), &champion_ids, timing).await {
+                Ok(outcome) => println!("{}", format_ban(&outcome, &catalog)),
+                Err(err) => println!("Auto ban failed, retrying: {}", err),
+            }
+            wait_n_millis(BAN_AHRI_THREAD_RETRY_MILLIS).await;
+        }
+    }
+
+    async fn status(&self) -> AppResult<()> {
+        println!("Riot path: {}", self.riot_path());
+        if !check_riot_path(self.riot_path()) {
+            return Err(AppError::RiotPathNotFound(self.riot_path()));
+        }
+        let lockfile = find_lockfile(self.riot_path()).await?;
+        println!("Client: {} (pid {}, port {})", lockfile.process_name, lockfile.pid, lockfile.port);
+        let client = LolClient::from_lockfile(&lockfile)?;
+        let phase = client.get_gameflow_phase().await?;
+        println!("Gameflow phase: {}", phase);
+        if phase == GameflowPhase::ChampSelect {
+            let session = client.get_champ_select_session().await?;
+            println!("Champ select phase: {} ({}s left)", session.timer.phase, session.timer.adjusted_time_left_in_phase / 1000);
+        }
+        Ok(())
+    }
+
+    async fn session(&self) -> AppResult<()> {
+        let client = LolClient::new(self.riot_path()).await?;
+        let session = match client.get::<serde_json::Value>(CHAMP_SELECT_SESSION_ENDPOINT).await {
+            Err(AppError::NotFound(_)) => return Err(AppError::NotInChampSelect(client.get_gameflow_phase().await?)),
+            result => result?,
+        };
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&session).map_err(|err| AppError::InvalidResponse(err.to_string()))?);
+            return Ok(());
+        }
+        let session = serde_json::from_value::<ChampSelectSession>(session)
+            .map_err(|err| AppError::InvalidResponse(err.to_string()))?;
+        let catalog = ChampionCatalog::load_cache(ChampionCatalog::cache_path()).unwrap_or_default();
+        println!("Game {}, phase {} ({}s left)", session.game_id, session.timer.phase, session.timer.adjusted_time_left_in_phase / 1000);
+        println!("Banned: {}", format_champions(&session.banned_champions(), &catalog));
+        println!("Picked: {}", format_champions(&session.picked_champions(), &catalog));
+        for action in session.local_actions("ban").chain(session.local_actions("pick")) {
+            let state = if action.completed {
+                "completed"
+            } else if action.is_in_progress {
+                "in progress"
+            } else {
+                "upcoming"
+            };
+            println!("Your {} ({}): {}", action.type_field, state, format_champions(&[action.champion_id as ChampionId], &catalog));
+        }
+        Ok(())
+    }
+
+    /// `--champion` values, or the saved ban list without any. Names need the champion catalog,
+    /// read from the cache or fetched from the client.
+    async fn resolve_champions(&self, config: &Config) -> AppResult<(Vec<ChampionId>, ChampionCatalog)> {
+        let mut catalog = ChampionCatalog::load_cache(ChampionCatalog::cache_path()).unwrap_or_default();
+        let needs_catalog = self.champions.iter().any(|champion| champion.trim().parse::<ChampionId>().is_err());
+        if needs_catalog && catalog.is_empty() {
+            catalog = ChampionCatalog::refresh(LolClient::new(self.riot_path()).await?).await?;
+        }
+        if self.champions.is_empty() {
+            return Ok((config.ban.champions.clone(), catalog));
+        }
+        let champion_ids = self.champions
+            .iter()
+            .map(|champion| {
+                catalog.resolve(champion).ok_or_else(|| AppError::UnknownChampion(champion.clone(), catalog.suggestions(champion)))
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+        Ok((champion_ids, catalog))
+    }
+}
+
+
+fn format_champions(champion_ids: &[ChampionId], catalog: &ChampionCatalog) -> String {
+    let names = champion_ids
+        .iter()
+        .filter(|&&champion_id| champion_id > 0)
+        .map(|&champion_id| catalog.name(champion_id))
+        .collect::<Vec<_>>();
+    if names.is_empty() {
+        "-".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+fn format_ban(outcome: &BanOutcome, catalog: &ChampionCatalog) -> String {
+    let mut message = match outcome.mode {
+        ActionMode::Hover => format!("Hovering {} until the ban phase starts", catalog.name(outcome.champion_id)),
+        ActionMode::LockIn => format!("Banned {}", catalog.name(outcome.champion_id)),
+    };
+    for (champion_id, reason) in &outcome.skipped {
+        message.push_str(&format!("\nSkipped {}: {}", catalog.name(*champion_id), reason));
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> AppResult<Cli> {
+        Cli::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_commands_and_options() {
+        let cli = parse(&["watch", "-c", "Ahri", "--champion", "84", "--late", "--accept-delay", "2000", "--riot-path", "D:/Riot Games"]).unwrap();
+        assert_eq!(cli.command, CliCommand::Watch);
+        // repeated champions are fallbacks, in order
+        assert_eq!(cli.champions, ["Ahri", "84"]);
+        assert_eq!(cli.accept_delay_millis, Some(2000));
+        assert_eq!(cli.riot_path.as_deref(), Some("D:/Riot Games"));
+        assert!(cli.late && !cli.auto_accept);
+        assert_eq!(parse(&["--help"]).unwrap().command, CliCommand::Help);
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        for args in [
+            &[][..],
+            &["--late"],
+            &["ban", "--force"],
+            &["ban", "-c"],
+            &["watch", "--accept-delay", "2s"],
+            &["watch", "--accept-delay", "-1"],
+            &["ban", "status"],
+            &["unban"],
+        ] {
+            let err = parse(args).unwrap_err();
+            assert!(matches!(err, AppError::InvalidArgument(_)), "{:?}: {:?}", args, err);
+            assert_eq!(err.exit_code(), 2);
+        }
+        assert_eq!(parse(&["status", "--riot-path"]), Err(AppError::InvalidArgument("--riot-path expects a value".to_string())));
+        assert_eq!(parse(&["session", "-x"]), Err(AppError::InvalidArgument("unknown option -x".to_string())));
+        assert_eq!(parse(&["-c", "Ahri"]), Err(AppError::InvalidArgument("missing command".to_string())));
+    }
+}
\ No newline at end of file
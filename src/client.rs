@@ -1,48 +1,276 @@
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 use base64::Engine;
 use base64::prelude::BASE64_STANDARD;
-use reqwest::Client;
+use reqwest::{Client, Response};
+use serde::de::DeserializeOwned;
 use tokio::sync::{mpsc, Mutex};
 
-use crate::{AHRI_ID, AppError, AppResult};
-use crate::client::champ_select_session::ChampSelectSession;
+use crate::{AHRI_ID, AppError, AppResult, ChampionId, wait_n_millis};
+use crate::client::api::LcuApi;
+use crate::client::champ_select_session::{ChampSelectSession, SkipReason};
+use crate::client::lockfile::Lockfile;
+use crate::client::process::scan_process;
+use crate::client::schedule::BanTiming;
+use crate::client::websocket::{auto_ban, LcuWebSocket};
 
+pub mod api;
 pub mod champ_select_session;
+pub mod champion_catalog;
+pub mod discovery;
+#[cfg(any(test, feature = "testing"))]
+pub mod fake_lcu;
+pub mod gameflow;
+pub mod lockfile;
+pub mod pick;
+pub mod process;
+pub mod ready_check;
+pub mod schedule;
+pub mod watcher;
+pub mod websocket;
+
+pub const CHAMP_SELECT_SESSION_ENDPOINT: &str = "/lol-champ-select/v1/session";
+pub const BANNABLE_CHAMPIONS_ENDPOINT: &str = "/lol-champ-select/v1/bannable-champion-ids";
+/// Default Windows install location.
+pub const DEFAULT_RIOT_PATH: &str = "C:\\Riot Games";
+pub const BAN_AHRI_THREAD_RETRY_MILLIS: u64 = 1000;
+pub const LCU_KEEPALIVE_SECS: u64 = 30;
+/// Interval between session reads while waiting for the client to confirm a ban.
+pub const VERIFY_BAN_POLL_MILLIS: u64 = 200;
+
+/// Root certificate the LCU serves its self-signed certificate from,
+/// see https://static.developer.riotgames.com/docs/lol/riotgames.pem.
+pub const RIOT_ROOT_CERTIFICATE: &[u8] = include_bytes!("../assets/riotgames.pem");
+
+static ACCEPT_INVALID_CERTS: AtomicBool = AtomicBool::new(false);
+
+/// Opt-out of certificate pinning, for setups where the LCU certificate isn't signed by [`RIOT_ROOT_CERTIFICATE`].
+/// Only clients built afterwards are affected.
+pub fn set_accept_invalid_certs(accept_invalid_certs: bool) {
+    ACCEPT_INVALID_CERTS.store(accept_invalid_certs, Ordering::Relaxed);
+}
+
+pub fn accept_invalid_certs() -> bool {
+    ACCEPT_INVALID_CERTS.load(Ordering::Relaxed)
+}
+
+/// TLS connector trusting only the Riot root certificate, or any certificate with `accept_invalid_certs`,
+/// shared by the http and websocket clients.
+/// The LCU certificate is issued for `127.0.0.1` without a matching subject alternative name, so hostnames aren't checked.
+pub fn tls_connector(accept_invalid_certs: bool) -> AppResult<native_tls::TlsConnector> {
+    let mut builder = native_tls::TlsConnector::builder();
+    builder.danger_accept_invalid_hostnames(true);
+    if accept_invalid_certs {
+        builder.danger_accept_invalid_certs(true);
+    } else {
+        let certificate = native_tls::Certificate::from_pem(RIOT_ROOT_CERTIFICATE)
+            .map_err(|err| AppError::RiotClientError(err.to_string()))?;
+        builder
+            .disable_built_in_roots(true)
+            .add_root_certificate(certificate);
+    }
+    builder.build().map_err(|err| AppError::RiotClientError(err.to_string()))
+}
 
 pub fn check_riot_path(riot_path: String) -> bool {
     PathBuf::from(riot_path).join("League of Legends").exists()
 }
 
+pub fn lockfile_path(riot_path: String) -> PathBuf {
+    PathBuf::from(riot_path).join("League of Legends").join("lockfile")
+}
+
+
+/// Client credentials from the lockfile, or from the `LeagueClientUx` command line when the lockfile
+/// is missing or stale. The lockfile error is kept if the process isn't found either.
+/// The port probe and the process scan block, they run on a blocking thread.
+pub async fn find_lockfile(riot_path: String) -> AppResult<Lockfile> {
+    tokio::task::spawn_blocking(move || {
+        let lockfile_path = lockfile_path(riot_path);
+        let lockfile = if lockfile_path.exists() {
+            Lockfile::read(lockfile_path)
+        } else {
+            Err(AppError::ClientNotRunning("lockfile not found".to_string()))
+        };
+        match lockfile {
+            Ok(lockfile) if lockfile.is_listening() => Ok(lockfile),
+            lockfile => scan_process().or(lockfile),
+        }
+    })
+        .await
+        .map_err(|err| AppError::IoError(err.to_string()))?
+}
+
 
 pub async fn ban_ahri(riot_path: String) -> AppResult<()> {
+    ban_champion_list(riot_path, &[AHRI_ID]).await.map(|_| ())
+}
+
+
+/// How an action is updated: hovered for teammates to see, or completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionMode {
+    Hover,
+    LockIn,
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BanOutcome {
+    pub champion_id: ChampionId,
+    pub mode: ActionMode,
+    /// Higher priority champions which couldn't be banned.
+    pub skipped: Vec<(ChampionId, SkipReason)>,
+}
+
+
+/// Bans the first champion of `champion_ids` which isn't banned yet, or hovers it during the planning phase.
+pub async fn ban_champion_list(riot_path: String, champion_ids: &[ChampionId]) -> AppResult<BanOutcome> {
+    ban_with_client(&LolClient::new(riot_path).await?, champion_ids).await
+}
+
+
+/// Like [`ban_champion_list`] with an already connected client.
+pub async fn ban_with_client(client: &impl LcuApi, champion_ids: &[ChampionId]) -> AppResult<BanOutcome> {
+    let champ_select_session = match client.get_champ_select_session().await {
+        Err(AppError::NotFound(_)) => return Err(AppError::NotInChampSelect(client.get_gameflow_phase().await?)),
+        result => result?,
+    };
+    ban_in_session(client, &champ_select_session, champion_ids).await
+}
+
+
+/// Waits for the local player's ban turn over the websocket, then bans like [`ban_champion_list`].
+pub async fn auto_ban_champion_list(riot_path: String, champion_ids: &[ChampionId], timing: BanTiming) -> AppResult<BanOutcome> {
+    let client = LolClient::new(riot_path).await?;
+    let mut socket = LcuWebSocket::connect(&client).await?;
+    auto_ban(&client, &mut socket, champion_ids, timing).await
+}
+
+
+/// A ban decided from a session ahead of time, so executing it is a single request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreparedBan {
+    pub action_id: i64,
+    pub champion_id: ChampionId,
+    pub mode: ActionMode,
+    pub skipped: Vec<(ChampionId, SkipReason)>,
+}
+
+
+/// Hovers the ban during `PLANNING` so teammates see it, and locks it during `BAN_PICK`.
+pub fn prepare_ban(champ_select_session: &ChampSelectSession, champion_ids: &[ChampionId], bannable_champions: &[ChampionId]) -> AppResult<PreparedBan> {
+    let (action, mode) = match champ_select_session.timer.phase.as_str() {
+        "PLANNING" => (
+            champ_select_session.next_action("ban")
+                .ok_or_else(|| AppError::RiotClientError("No ban action left".to_string()))?,
+            ActionMode::Hover,
+        ),
+        "BAN_PICK" => (
+            champ_select_session.current_ban_action()
+                .ok_or_else(|| AppError::RiotClientError("No ban action in progress".to_string()))?,
+            ActionMode::LockIn,
+        ),
+        _ => return Err(AppError::RiotClientError("Not in Ban Phase".to_string())),
+    };
+    let (champion_id, skipped) = champ_select_session.choose_ban(champion_ids, bannable_champions);
+    let Some(champion_id) = champion_id else {
+        return Err(AppError::NoChampionToBan(skipped));
+    };
+    Ok(PreparedBan { action_id: action.id, champion_id, mode, skipped })
+}
+
+
+/// Sends the prepared ban, then waits for the client to confirm a locked one.
+pub async fn execute_ban(client: &impl LcuApi, prepared_ban: PreparedBan) -> AppResult<BanOutcome> {
     let start = std::time::Instant::now();
-    let client = LolClient::new(riot_path)?;
-    let champ_select_session = client.get_champ_select_session().await?;
-    if champ_select_session.timer.phase != "BAN_PICK" {
-        return Err(AppError::RiotClientError("Not in Ban Phase".to_string()));
-    }
-    if champ_select_session.bans.my_team_bans.iter().any(|&champion_id| champion_id == AHRI_ID)
-        || champ_select_session.bans.their_team_bans.iter().any(|&champion_id| champion_id == AHRI_ID) {
-        return Err(AppError::RiotClientError("Ahri already banned".to_string()));
-    }
-    for actions in &champ_select_session.actions {
-        for action in actions {
-            if action.actor_cell_id == champ_select_session.local_player_cell_id && action.type_field == "ban" {
-                client.ban_champion(AHRI_ID, action.id).await?;
-                println!("Ahri banned in {:?}ms", start.elapsed().as_millis());
-                return Ok(());
-            }
+    let PreparedBan { action_id, champion_id, mode, skipped } = prepared_ban;
+    for (skipped_champion_id, reason) in &skipped {
+        println!("Skipped champion {}: {}", skipped_champion_id, reason);
+    }
+    client.ban_champion(champion_id, action_id, mode).await?;
+    println!("Champion {} {:?} in {:?}ms", champion_id, mode, start.elapsed().as_millis());
+    if mode == ActionMode::Hover {
+        return Ok(BanOutcome { champion_id, mode, skipped });
+    }
+    let banned_champion_id = verify_ban(client, action_id, champion_id).await?;
+    if banned_champion_id != champion_id {
+        println!("Champion {} banned instead of {}", banned_champion_id, champion_id);
+    }
+    println!("Ban of {} confirmed in {:?}ms", banned_champion_id, start.elapsed().as_millis());
+    Ok(BanOutcome { champion_id: banned_champion_id, mode, skipped })
+}
+
+
+/// Prepares the ban from `champ_select_session` with fresh bannable champions and executes it.
+pub async fn ban_in_session(client: &impl LcuApi, champ_select_session: &ChampSelectSession, champion_ids: &[ChampionId]) -> AppResult<BanOutcome> {
+    let bannable_champions = client.get_bannable_champion_ids().await?;
+    execute_ban(client, prepare_ban(champ_select_session, champion_ids, &bannable_champions)?).await
+}
+
+
+/// Re-reads the session until the ban action is completed, locking it again while the phase leaves time for it,
+/// since the client sometimes accepts the update without applying it. Returns the champion actually banned.
+pub async fn verify_ban(client: &impl LcuApi, action_id: i64, champion_id: ChampionId) -> AppResult<ChampionId> {
+    loop {
+        wait_n_millis(VERIFY_BAN_POLL_MILLIS).await;
+        let session = client.get_champ_select_session().await?;
+        let Some(action) = session.action(action_id) else {
+            return Err(AppError::BanNotConfirmed(champion_id));
+        };
+        if action.completed {
+            return Ok(action.champion_id as ChampionId);
+        }
+        let has_time_left = session.timer.phase == "BAN_PICK"
+            && session.timer.adjusted_time_left_in_phase > VERIFY_BAN_POLL_MILLIS as i64;
+        if !action.is_in_progress || !has_time_left {
+            return Err(AppError::BanNotConfirmed(champion_id));
+        }
+        println!("Ban of {} not completed yet, locking it again", champion_id);
+        // the previous update may land meanwhile and make this one fail, the next read tells
+        if let Err(err) = client.ban_champion(champion_id, action_id, ActionMode::LockIn).await {
+            println!("Failed to lock ban again: {:?}", err);
+        }
+    }
+}
+
+
+/// Keeps auto-banning armed until a ban succeeds (`Some`) or a stop is received on `receiver` (`None`).
+/// Connection and ban failures are retried, so the thread can be armed before the client starts.
+pub async fn ban_ahri_thread(riot_path: String, champion_ids: Vec<ChampionId>, timing: BanTiming, mut receiver: mpsc::Receiver<bool>) -> AppResult<Option<BanOutcome>> {
+    let mut lol_client = None;
+    loop {
+        tokio::select! {
+            _ = receiver.recv() => return Ok(None),
+            result = async {
+                // kept across retries, the client is only looked up again once it stops connecting
+                let client = match lol_client.take() {
+                    Some(client) => client,
+                    None => LolClient::new(riot_path.clone()).await?,
+                };
+                let mut socket = LcuWebSocket::connect(&client).await?;
+                lol_client = Some(client.clone());
+                auto_ban(&client, &mut socket, &champion_ids, timing).await
+            } => match result {
+                Ok(outcome) => return Ok(Some(outcome)),
+                Err(err) => println!("Auto ban failed, retrying: {:?}", err),
+            },
+        }
+        tokio::select! {
+            _ = receiver.recv() => return Ok(None),
+            _ = wait_n_millis(BAN_AHRI_THREAD_RETRY_MILLIS) => {}
         }
     }
-    Err(AppError::RiotClientError("No ban action found".to_string()))
 }
 
 
 pub async fn stop_ban_ahri_thread(sender: Arc<Mutex<mpsc::Sender<bool>>>) -> AppResult<()> {
     let sender = sender.lock().await;
-    sender.send(true).await.unwrap();
+    // the thread is already gone if it banned in the meantime, nothing left to stop
+    let _ = sender.send(true).await;
     Ok(())
 }
 
@@ -51,38 +279,50 @@ pub async fn stop_ban_ahri_thread(sender: Arc<Mutex<mpsc::Sender<bool>>>) -> App
 pub struct LolClient {
     pub client: Client,
     pub port: String,
+    pub password: String,
+    /// Whether certificates which aren't signed by [`RIOT_ROOT_CERTIFICATE`] are trusted, also by the websocket.
+    pub accept_invalid_certs: bool,
 }
 
 
 impl LolClient {
-    pub fn new(riot_path: String) -> AppResult<Self> {
-        let lol_path = PathBuf::from(riot_path).join("League of Legends");
+    pub async fn new(riot_path: String) -> AppResult<Self> {
+        let lol_path = PathBuf::from(&riot_path).join("League of Legends");
         if !lol_path.exists() {
-            return Err(AppError::IoError("League of Legends not found".to_string()));
-        }
-        let lockfile_path = lol_path
-            .join("lockfile");
-        if lockfile_path.exists() {
-            let lockfile = std::fs::read_to_string(lockfile_path)?;
-            let lockfile_parts: Vec<&str> = lockfile.split(':').collect();
-
-            let port = lockfile_parts[2].to_string();
-            let password = lockfile_parts[3];
-
-            let mut headers = reqwest::header::HeaderMap::new();
-            let auth = BASE64_STANDARD.encode(format!("riot:{}", password).as_str());
-            headers.insert("Authorization", format!("Basic {}", auth).parse().unwrap());
-
-            let client = Client::builder()
-                .danger_accept_invalid_certs(true)
-                .danger_accept_invalid_hostnames(true)
-                .default_headers(headers)
-                .build()
-                .unwrap();
-            Ok(Self { port, client })
-        } else {
-            Err(AppError::IoError("lockfile not found".to_string()))
+            return Err(AppError::RiotPathNotFound(riot_path));
         }
+        Self::from_lockfile(&find_lockfile(riot_path).await?)
+    }
+
+    pub fn from_lockfile(lockfile: &Lockfile) -> AppResult<Self> {
+        Self::from_lockfile_with(lockfile, accept_invalid_certs())
+    }
+
+    /// Like [`Self::from_lockfile`], without reading the process wide [`set_accept_invalid_certs`].
+    pub fn from_lockfile_with(lockfile: &Lockfile, accept_invalid_certs: bool) -> AppResult<Self> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        let auth = BASE64_STANDARD.encode(format!("riot:{}", lockfile.password).as_str());
+        headers.insert("Authorization", format!("Basic {}", auth).parse().unwrap());
+
+        // the connection is kept open between requests, a ban shouldn't wait for a TLS handshake
+        let client = Client::builder()
+            .use_preconfigured_tls(tls_connector(accept_invalid_certs)?)
+            .default_headers(headers)
+            .pool_idle_timeout(None)
+            .tcp_keepalive(Duration::from_secs(LCU_KEEPALIVE_SECS))
+            .tcp_nodelay(true)
+            .build()?;
+        Ok(Self {
+            port: lockfile.port.to_string(),
+            password: lockfile.password.clone(),
+            accept_invalid_certs,
+            client,
+        })
+    }
+
+    /// Opens the pooled connection ahead of the first latency sensitive request.
+    pub async fn warm_up(&self) -> AppResult<()> {
+        self.get_gameflow_phase().await.map(|_| ())
     }
 
     pub fn get_url(&self, endpoint: &str) -> String {
@@ -90,29 +330,233 @@ impl LolClient {
     }
 
 
-    pub async fn ban_champion(&self, champion_id: i32, action_id: i64) -> AppResult<()> {
-        let url = self.get_url(format!("/lol-champ-select/v1/session/actions/{}", action_id).as_str());
-        self.client.patch(url)
-            .json(&serde_json::json!({
-            "championId": champion_id,
-            "completed": true,
-        }))
-            .send()
+    /// Turns a non 2xx response into the matching [`AppError`], keeping the LCU error body.
+    pub async fn check_response(response: Response) -> AppResult<Response> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body = response.text().await.unwrap_or_default();
+        Err(AppError::from_lcu_response(status.as_u16(), &body))
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, endpoint: &str) -> AppResult<T> {
+        let response = self.client.get(self.get_url(endpoint)).send().await?;
+        Self::check_response(response)
             .await?
-            .error_for_status()?;
+            .json::<T>()
+            .await
+            .map_err(|err| AppError::InvalidResponse(err.to_string()))
+    }
+
+    pub async fn patch(&self, endpoint: &str, body: &serde_json::Value) -> AppResult<()> {
+        let response = self.client.patch(self.get_url(endpoint)).json(body).send().await?;
+        Self::check_response(response).await?;
         Ok(())
     }
 
+    pub async fn post(&self, endpoint: &str, body: Option<&serde_json::Value>) -> AppResult<()> {
+        let mut request = self.client.post(self.get_url(endpoint));
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        Self::check_response(request.send().await?).await?;
+        Ok(())
+    }
+
+
+    /// Champions which can be banned in the current champ select, e.g. not disabled in this mode.
+    pub async fn get_bannable_champion_ids(&self) -> AppResult<Vec<ChampionId>> {
+        self.get(BANNABLE_CHAMPIONS_ENDPOINT).await
+    }
+
+    pub async fn update_action(&self, champion_id: ChampionId, action_id: i64, mode: ActionMode) -> AppResult<()> {
+        let body = match mode {
+            ActionMode::Hover => serde_json::json!({
+                "championId": champion_id,
+            }),
+            ActionMode::LockIn => serde_json::json!({
+                "championId": champion_id,
+                "completed": true,
+            }),
+        };
+        self.patch(format!("/lol-champ-select/v1/session/actions/{}", action_id).as_str(), &body).await
+    }
+
 
     pub async fn get_champ_select_session(&self) -> AppResult<ChampSelectSession> {
-        let url = self.get_url("/lol-champ-select/v1/session");
-        let response = self.client.get(&url).send().await?;
-        response.json::<ChampSelectSession>().await.map_err(|_| AppError::RiotClientError("No Champ Select Session".to_string()))
+        self.get(CHAMP_SELECT_SESSION_ENDPOINT).await
     }
 
     pub async fn get_user_session(&self) -> AppResult<serde_json::Value> {
-        let url = self.get_url("/lol-chat/v1/me");
-        let response = self.client.get(&url).send().await?;
-        response.json().await.map_err(|_| AppError::RiotClientError("No User Session".to_string()))
+        self.get("/lol-chat/v1/me").await
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::fake_lcu::{ActionUpdate, FakeLcu, FakeState};
+    use crate::client::gameflow::GameflowPhase;
+    use crate::errors::LcuErrorResponse;
+    use crate::mock_lcu::{MockLcu, MockState};
+
+    const BAN_ACTION_ID: i64 = 1;
+    const YASUO_ID: ChampionId = 157;
+
+    /// The local player's ban, with a teammate hovering Yasuo.
+    fn ban_session(timer_phase: &str, is_in_progress: bool, banned_champion_id: Option<ChampionId>) -> ChampSelectSession {
+        serde_json::from_value(serde_json::json!({
+            "actions": [[
+                {"actorCellId": 0, "championId": banned_champion_id.unwrap_or_default(), "completed": banned_champion_id.is_some(), "id": BAN_ACTION_ID, "isAllyAction": true, "isInProgress": is_in_progress, "pickTurn": 1, "type": "ban"},
+                {"actorCellId": 5, "championId": 84, "completed": true, "id": 2, "isAllyAction": false, "isInProgress": false, "pickTurn": 1, "type": "ban"}
+            ]],
+            "bans": {"myTeamBans": [], "numBans": 2, "theirTeamBans": []},
+            "gameId": 1,
+            "localPlayerCellId": 0,
+            "myTeam": [{"cellId": 0}, {"cellId": 1, "championPickIntent": YASUO_ID}],
+            "timer": {"adjustedTimeLeftInPhase": 20000, "internalNowInEpochMs": 0, "isInfinite": false, "phase": timer_phase, "totalTimeInPhase": 30000}
+        })).unwrap()
+    }
+
+    fn lock_in(champion_id: ChampionId) -> ActionUpdate {
+        ActionUpdate { champion_id, action_id: BAN_ACTION_ID, mode: ActionMode::LockIn }
+    }
+
+    fn rpc_error() -> AppError {
+        AppError::RpcError(LcuErrorResponse {
+            error_code: "RPC_ERROR".to_string(),
+            http_status: 500,
+            message: "Unable to process action change: Received status Error: INVALID_STATE".to_string(),
+        })
+    }
+
+    #[test]
+    fn prepares_hover_during_planning_and_lock_in_on_the_turn() {
+        let champion_ids = [YASUO_ID, 84, AHRI_ID];
+        let bannable_champions = [YASUO_ID, AHRI_ID];
+        let hover = prepare_ban(&ban_session("PLANNING", false, None), &champion_ids, &bannable_champions).unwrap();
+        assert_eq!(hover, PreparedBan {
+            action_id: BAN_ACTION_ID,
+            champion_id: AHRI_ID,
+            mode: ActionMode::Hover,
+            skipped: vec![(YASUO_ID, SkipReason::TeammateIntent), (84, SkipReason::AlreadyBanned)],
+        });
+        let lock_in = prepare_ban(&ban_session("BAN_PICK", true, None), &champion_ids, &bannable_champions).unwrap();
+        assert_eq!((lock_in.champion_id, lock_in.mode), (AHRI_ID, ActionMode::LockIn));
+    }
+
+    #[test]
+    fn prepare_ban_fails_without_a_ban_to_make() {
+        assert_eq!(
+            prepare_ban(&ban_session("BAN_PICK", true, None), &[AHRI_ID], &[]),
+            Err(AppError::NoChampionToBan(vec![(AHRI_ID, SkipReason::NotBannable)])),
+        );
+        // waiting for the other team
+        assert!(matches!(prepare_ban(&ban_session("BAN_PICK", false, None), &[AHRI_ID], &[AHRI_ID]), Err(AppError::RiotClientError(_))));
+        assert!(matches!(prepare_ban(&ban_session("FINALIZATION", false, None), &[AHRI_ID], &[AHRI_ID]), Err(AppError::RiotClientError(_))));
+    }
+
+    /// The local player banned Akali already and has a second ban, e.g. in a custom game with fewer players.
+    fn second_ban_session(timer_phase: &str, is_in_progress: bool) -> ChampSelectSession {
+        serde_json::from_value(serde_json::json!({
+            "actions": [[
+                {"actorCellId": 0, "championId": 84, "completed": true, "id": 1, "isAllyAction": true, "isInProgress": false, "pickTurn": 1, "type": "ban"},
+                {"actorCellId": 5, "championId": 0, "completed": false, "id": 2, "isAllyAction": false, "isInProgress": false, "pickTurn": 1, "type": "ban"}
+            ], [
+                {"actorCellId": 0, "championId": 0, "completed": false, "id": 3, "isAllyAction": true, "isInProgress": is_in_progress, "pickTurn": 2, "type": "ban"}
+            ]],
+            "bans": {"myTeamBans": [84], "numBans": 3, "theirTeamBans": []},
+            "gameId": 1,
+            "localPlayerCellId": 0,
+            "timer": {"adjustedTimeLeftInPhase": 20000, "internalNowInEpochMs": 0, "isInfinite": false, "phase": timer_phase, "totalTimeInPhase": 30000}
+        })).unwrap()
+    }
+
+    #[test]
+    fn prepares_the_next_ban_after_a_completed_one() {
+        let planning = second_ban_session("PLANNING", false);
+        let hover = prepare_ban(&planning, &[84, AHRI_ID], &[84, AHRI_ID]).unwrap();
+        assert_eq!((hover.action_id, hover.champion_id, hover.mode), (3, AHRI_ID, ActionMode::Hover));
+
+        let lock_in = prepare_ban(&second_ban_session("BAN_PICK", true), &[84, AHRI_ID], &[84, AHRI_ID]).unwrap();
+        assert_eq!((lock_in.action_id, lock_in.champion_id, lock_in.mode), (3, AHRI_ID, ActionMode::LockIn));
+        assert_eq!(lock_in.skipped, [(84, SkipReason::AlreadyBanned)]);
+    }
+
+    #[tokio::test]
+    async fn ban_with_client_locks_and_confirms() {
+        let fake = FakeLcu::with_sessions([ban_session("BAN_PICK", true, None), ban_session("BAN_PICK", false, Some(AHRI_ID))]);
+        fake.state().bannable_champions = vec![AHRI_ID];
+        let outcome = ban_with_client(&fake, &[AHRI_ID]).await.unwrap();
+        assert_eq!(outcome, BanOutcome { champion_id: AHRI_ID, mode: ActionMode::LockIn, skipped: Vec::new() });
+        assert_eq!(fake.state().action_updates, [lock_in(AHRI_ID)]);
+    }
+
+    #[tokio::test]
+    async fn ban_with_client_outside_of_champ_select() {
+        let fake = FakeLcu::new(FakeState {
+            phase: GameflowPhase::Lobby,
+            ..Default::default()
+        });
+        assert_eq!(ban_with_client(&fake, &[AHRI_ID]).await, Err(AppError::NotInChampSelect(GameflowPhase::Lobby)));
+        assert!(fake.state().action_updates.is_empty());
+    }
+
+    #[tokio::test]
+    async fn verify_ban_locks_again_until_completed() {
+        let fake = FakeLcu::with_sessions([
+            ban_session("BAN_PICK", true, None),
+            ban_session("BAN_PICK", true, None),
+            ban_session("BAN_PICK", false, Some(AHRI_ID)),
+        ]);
+        // the first lock again is refused by the busy client
+        fake.state().update_errors.push_back(rpc_error());
+        assert_eq!(verify_ban(&fake, BAN_ACTION_ID, AHRI_ID).await, Ok(AHRI_ID));
+        assert_eq!(fake.state().action_updates, [lock_in(AHRI_ID), lock_in(AHRI_ID)]);
+    }
+
+    #[tokio::test]
+    async fn verify_ban_reports_the_champion_actually_banned() {
+        let fake = FakeLcu::with_sessions([ban_session("BAN_PICK", false, Some(YASUO_ID))]);
+        assert_eq!(verify_ban(&fake, BAN_ACTION_ID, AHRI_ID).await, Ok(YASUO_ID));
+        assert!(fake.state().action_updates.is_empty());
+    }
+
+    #[tokio::test]
+    async fn verify_ban_gives_up_once_the_turn_is_over() {
+        let fake = FakeLcu::with_sessions([ban_session("BAN_PICK", false, None)]);
+        assert_eq!(verify_ban(&fake, BAN_ACTION_ID, AHRI_ID).await, Err(AppError::BanNotConfirmed(AHRI_ID)));
+        let fake = FakeLcu::with_sessions([ban_session("BAN_PICK", true, None)]);
+        assert_eq!(verify_ban(&fake, 99, AHRI_ID).await, Err(AppError::BanNotConfirmed(AHRI_ID)));
+        assert!(fake.state().action_updates.is_empty());
+    }
+
+    async fn start_mock() -> MockLcu {
+        MockLcu::start(0, MockState {
+            phase: GameflowPhase::Lobby,
+            ..Default::default()
+        }).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn self_signed_certificate_is_rejected() {
+        let mock = start_mock().await;
+        let client = LolClient::from_lockfile_with(&mock.lockfile(), false).unwrap();
+        let err = client.get_gameflow_phase().await.unwrap_err();
+        assert!(matches!(err, AppError::CertificateRejected(_)), "{:?}", err);
+        assert_eq!(err.exit_code(), 13);
+
+        let err = LcuWebSocket::connect(&client).await.err().unwrap();
+        assert!(matches!(err, AppError::CertificateRejected(_)), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn self_signed_certificate_is_accepted_when_allowed() {
+        let mock = start_mock().await;
+        let client = LolClient::from_lockfile_with(&mock.lockfile(), true).unwrap();
+        assert_eq!(client.get_gameflow_phase().await.unwrap(), GameflowPhase::Lobby);
+        assert!(LcuWebSocket::connect(&client).await.is_ok());
     }
 }
\ No newline at end of file
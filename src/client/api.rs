@@ -0,0 +1,96 @@
+use std::future::Future;
+
+use crate::{AppResult, ChampionId};
+use crate::client::{ActionMode, LolClient};
+use crate::client::champ_select_session::ChampSelectSession;
+use crate::client::gameflow::{GameflowPhase, GameflowSession};
+use crate::client::ready_check::ReadyCheck;
+
+/// The LCU endpoints the ban, pick, ready check and gameflow logic relies on.
+/// [`LolClient`] talks to the running client, `FakeLcu` of the `testing` feature answers from memory.
+pub trait LcuApi: Sync {
+    fn get_champ_select_session(&self) -> impl Future<Output = AppResult<ChampSelectSession>> + Send;
+
+    /// Champions which can be banned in the current champ select, e.g. not disabled in this mode.
+    fn get_bannable_champion_ids(&self) -> impl Future<Output = AppResult<Vec<ChampionId>>> + Send;
+
+    fn get_pickable_champion_ids(&self) -> impl Future<Output = AppResult<Vec<ChampionId>>> + Send;
+
+    /// Hovers `champion_id` on the action, or locks it in.
+    fn update_action(&self, champion_id: ChampionId, action_id: i64, mode: ActionMode) -> impl Future<Output = AppResult<()>> + Send;
+
+    fn get_gameflow_phase(&self) -> impl Future<Output = AppResult<GameflowPhase>> + Send;
+
+    fn get_gameflow_session(&self) -> impl Future<Output = AppResult<GameflowSession>> + Send;
+
+    /// Chat presence of the logged in player.
+    fn get_user_session(&self) -> impl Future<Output = AppResult<serde_json::Value>> + Send;
+
+    fn get_ready_check(&self) -> impl Future<Output = AppResult<ReadyCheck>> + Send;
+
+    fn accept_ready_check(&self) -> impl Future<Output = AppResult<()>> + Send;
+
+    fn decline_ready_check(&self) -> impl Future<Output = AppResult<()>> + Send;
+
+    /// Hovers `champion_id` on the ban action, or locks it in.
+    fn ban_champion(&self, champion_id: ChampionId, action_id: i64, mode: ActionMode) -> impl Future<Output = AppResult<()>> + Send {
+        self.update_action(champion_id, action_id, mode)
+    }
+
+    /// Hovers `champion_id` on the pick action, or locks it in.
+    fn pick_champion(&self, champion_id: ChampionId, action_id: i64, mode: ActionMode) -> impl Future<Output = AppResult<()>> + Send {
+        self.update_action(champion_id, action_id, mode)
+    }
+
+    /// Whether the chat availability of the logged in player is `away`.
+    fn is_away(&self) -> impl Future<Output = AppResult<bool>> + Send {
+        async {
+            let user_session = self.get_user_session().await?;
+            Ok(user_session["availability"] == "away")
+        }
+    }
+}
+
+
+/// Each endpoint is implemented next to the types it returns.
+impl LcuApi for LolClient {
+    fn get_champ_select_session(&self) -> impl Future<Output = AppResult<ChampSelectSession>> + Send {
+        LolClient::get_champ_select_session(self)
+    }
+
+    fn get_bannable_champion_ids(&self) -> impl Future<Output = AppResult<Vec<ChampionId>>> + Send {
+        LolClient::get_bannable_champion_ids(self)
+    }
+
+    fn get_pickable_champion_ids(&self) -> impl Future<Output = AppResult<Vec<ChampionId>>> + Send {
+        LolClient::get_pickable_champion_ids(self)
+    }
+
+    fn update_action(&self, champion_id: ChampionId, action_id: i64, mode: ActionMode) -> impl Future<Output = AppResult<()>> + Send {
+        LolClient::update_action(self, champion_id, action_id, mode)
+    }
+
+    fn get_gameflow_phase(&self) -> impl Future<Output = AppResult<GameflowPhase>> + Send {
+        LolClient::get_gameflow_phase(self)
+    }
+
+    fn get_gameflow_session(&self) -> impl Future<Output = AppResult<GameflowSession>> + Send {
+        LolClient::get_gameflow_session(self)
+    }
+
+    fn get_user_session(&self) -> impl Future<Output = AppResult<serde_json::Value>> + Send {
+        LolClient::get_user_session(self)
+    }
+
+    fn get_ready_check(&self) -> impl Future<Output = AppResult<ReadyCheck>> + Send {
+        LolClient::get_ready_check(self)
+    }
+
+    fn accept_ready_check(&self) -> impl Future<Output = AppResult<()>> + Send {
+        LolClient::accept_ready_check(self)
+    }
+
+    fn decline_ready_check(&self) -> impl Future<Output = AppResult<()>> + Send {
+        LolClient::decline_ready_check(self)
+    }
+}
\ No newline at end of file
@@ -1,17 +1,194 @@
 use serde_derive::Deserialize;
 use serde_derive::Serialize;
 
+use crate::ChampionId;
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ChampSelectSession {
     pub actions: Vec<Vec<Action>>,
+    #[serde(rename = "allowRerolling", default)]
+    pub allow_rerolling: bool,
     pub bans: Bans,
+    #[serde(rename = "benchChampions", default)]
+    pub bench_champions: Vec<BenchChampion>,
+    #[serde(rename = "benchEnabled", default)]
+    pub bench_enabled: bool,
+    #[serde(rename = "chatDetails", default)]
+    pub chat_details: ChatDetails,
     #[serde(rename = "gameId")]
     pub game_id: i64,
+    #[serde(rename = "isSpectating", default)]
+    pub is_spectating: bool,
     #[serde(rename = "localPlayerCellId")]
     pub local_player_cell_id: i64,
+    #[serde(rename = "myTeam", default)]
+    pub my_team: Vec<TeamMember>,
+    #[serde(rename = "pickOrderSwaps", default)]
+    pub pick_order_swaps: Vec<SwapRequest>,
+    #[serde(rename = "recoveryCounter", default)]
+    pub recovery_counter: i64,
+    #[serde(rename = "rerollsRemaining", default)]
+    pub rerolls_remaining: i64,
+    #[serde(rename = "theirTeam", default)]
+    pub their_team: Vec<TeamMember>,
     pub timer: Timer,
+    #[serde(default)]
+    pub trades: Vec<SwapRequest>,
+}
+
+impl ChampSelectSession {
+    /// Actions of the local player with the given type (`ban`, `pick`...), in action group order.
+    pub fn local_actions<'a, 'b>(&'a self, action_type: &'b str) -> impl Iterator<Item=&'a Action> + 'b
+    where
+        'a: 'b,
+    {
+        self.actions
+            .iter()
+            .flatten()
+            .filter(move |action| action.actor_cell_id == self.local_player_cell_id && action.type_field == action_type)
+    }
+
+    /// The local player's action of the given type which is running and not completed yet.
+    /// Custom drafts can give several ban actions to the same player, only the active one can be completed.
+    pub fn current_action(&self, action_type: &str) -> Option<&Action> {
+        self.local_actions(action_type).find(|action| action.is_in_progress && !action.completed)
+    }
+
+    /// The local player's first action of the given type which isn't completed, running or upcoming.
+    pub fn next_action(&self, action_type: &str) -> Option<&Action> {
+        self.local_actions(action_type).find(|action| !action.completed)
+    }
+
+    pub fn current_ban_action(&self) -> Option<&Action> {
+        self.current_action("ban")
+    }
+
+    pub fn action(&self, action_id: i64) -> Option<&Action> {
+        self.actions.iter().flatten().find(|action| action.id == action_id)
+    }
+
+    /// Champions banned by either team, including completed ban actions not reported in `bans` yet.
+    pub fn banned_champions(&self) -> Vec<ChampionId> {
+        self.bans.my_team_bans
+            .iter()
+            .chain(&self.bans.their_team_bans)
+            .copied()
+            .chain(self.actions
+                .iter()
+                .flatten()
+                .filter(|action| action.type_field == "ban" && action.completed)
+                .map(|action| action.champion_id as ChampionId))
+            .filter(|&champion_id| champion_id > 0)
+            .collect()
+    }
+
+    /// Champions hovered, intended or picked by the local player's teammates.
+    pub fn teammate_champions(&self) -> Vec<ChampionId> {
+        self.my_team
+            .iter()
+            .filter(|member| member.cell_id != self.local_player_cell_id)
+            .flat_map(|member| [member.champion_id, member.champion_pick_intent])
+            .chain(self.actions
+                .iter()
+                .flatten()
+                .filter(|action| action.type_field == "pick"
+                    && action.is_ally_action
+                    && action.actor_cell_id != self.local_player_cell_id)
+                .map(|action| action.champion_id as ChampionId))
+            .filter(|&champion_id| champion_id > 0)
+            .collect()
+    }
+
+    /// Assigned position of the local player, empty in blind pick.
+    pub fn local_position(&self) -> &str {
+        self.my_team
+            .iter()
+            .find(|member| member.cell_id == self.local_player_cell_id)
+            .map(|member| member.assigned_position.as_str())
+            .unwrap_or_default()
+    }
+
+    /// Champions picked by either team, the local player excepted.
+    pub fn picked_champions(&self) -> Vec<ChampionId> {
+        self.my_team
+            .iter()
+            .chain(&self.their_team)
+            .filter(|member| member.cell_id != self.local_player_cell_id)
+            .map(|member| member.champion_id)
+            .chain(self.actions
+                .iter()
+                .flatten()
+                .filter(|action| action.type_field == "pick"
+                    && action.completed
+                    && action.actor_cell_id != self.local_player_cell_id)
+                .map(|action| action.champion_id as ChampionId))
+            .filter(|&champion_id| champion_id > 0)
+            .collect()
+    }
+
+    /// Picks the first of `champion_ids` which can be banned, with the reason each earlier one was skipped.
+    pub fn choose_ban(&self, champion_ids: &[ChampionId], bannable_champions: &[ChampionId]) -> (Option<ChampionId>, Vec<(ChampionId, SkipReason)>) {
+        let banned_champions = self.banned_champions();
+        let teammate_champions = self.teammate_champions();
+        let mut skipped = Vec::new();
+        for &champion_id in champion_ids {
+            if banned_champions.contains(&champion_id) {
+                skipped.push((champion_id, SkipReason::AlreadyBanned));
+            } else if teammate_champions.contains(&champion_id) {
+                skipped.push((champion_id, SkipReason::TeammateIntent));
+            } else if !bannable_champions.contains(&champion_id) {
+                skipped.push((champion_id, SkipReason::NotBannable));
+            } else {
+                return (Some(champion_id), skipped);
+            }
+        }
+        (None, skipped)
+    }
+
+    /// Picks the first of `champion_ids` which can be picked, with the reason each earlier one was skipped.
+    pub fn choose_pick(&self, champion_ids: &[ChampionId], pickable_champions: &[ChampionId]) -> (Option<ChampionId>, Vec<(ChampionId, SkipReason)>) {
+        let banned_champions = self.banned_champions();
+        let picked_champions = self.picked_champions();
+        let mut skipped = Vec::new();
+        for &champion_id in champion_ids {
+            if banned_champions.contains(&champion_id) {
+                skipped.push((champion_id, SkipReason::AlreadyBanned));
+            } else if picked_champions.contains(&champion_id) {
+                skipped.push((champion_id, SkipReason::AlreadyPicked));
+            } else if !pickable_champions.contains(&champion_id) {
+                skipped.push((champion_id, SkipReason::NotPickable));
+            } else {
+                return (Some(champion_id), skipped);
+            }
+        }
+        (None, skipped)
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    AlreadyBanned,
+    TeammateIntent,
+    AlreadyPicked,
+    NotPickable,
+    /// Disabled in this mode, not released yet or otherwise refused by the client.
+    NotBannable,
 }
 
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::AlreadyBanned => write!(f, "already banned"),
+            SkipReason::TeammateIntent => write!(f, "hovered or picked by a teammate"),
+            SkipReason::AlreadyPicked => write!(f, "already picked"),
+            SkipReason::NotPickable => write!(f, "not pickable"),
+            SkipReason::NotBannable => write!(f, "not bannable"),
+        }
+    }
+}
+
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Action {
     #[serde(rename = "actorCellId")]
@@ -52,4 +229,143 @@ pub struct Timer {
     pub phase: String,
     #[serde(rename = "totalTimeInPhase")]
     pub total_time_in_phase: i64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TeamMember {
+    #[serde(rename = "assignedPosition")]
+    pub assigned_position: String,
+    #[serde(rename = "cellId")]
+    pub cell_id: i64,
+    #[serde(rename = "championId")]
+    pub champion_id: ChampionId,
+    #[serde(rename = "championPickIntent")]
+    pub champion_pick_intent: ChampionId,
+    pub puuid: String,
+    #[serde(rename = "selectedSkinId")]
+    pub selected_skin_id: i64,
+    /// Spell ids are `u64::MAX` while no spell is selected.
+    #[serde(rename = "spell1Id")]
+    pub spell1_id: u64,
+    #[serde(rename = "spell2Id")]
+    pub spell2_id: u64,
+    #[serde(rename = "summonerId")]
+    pub summoner_id: u64,
+    pub team: i64,
+    #[serde(rename = "wardSkinId")]
+    pub ward_skin_id: i64,
+}
+
+
+/// A trade or pick order swap offered to another cell.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SwapRequest {
+    #[serde(rename = "cellId")]
+    pub cell_id: i64,
+    pub id: i64,
+    /// `AVAILABLE`, `BUSY`, `INVALID`, `RECEIVED` or `SENT`.
+    pub state: String,
+}
+
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BenchChampion {
+    #[serde(rename = "championId")]
+    pub champion_id: ChampionId,
+    #[serde(rename = "isPriority")]
+    pub is_priority: bool,
+}
+
+
+/// Champ select chat room, older clients send `chatRoomName`/`chatRoomPassword` instead of the multi user chat fields.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChatDetails {
+    #[serde(rename = "chatRoomName", skip_serializing_if = "Option::is_none")]
+    pub chat_room_name: Option<String>,
+    #[serde(rename = "chatRoomPassword", skip_serializing_if = "Option::is_none")]
+    pub chat_room_password: Option<String>,
+    #[serde(rename = "multiUserChatId", skip_serializing_if = "Option::is_none")]
+    pub multi_user_chat_id: Option<String>,
+    #[serde(rename = "multiUserChatPassword", skip_serializing_if = "Option::is_none")]
+    pub multi_user_chat_password: Option<String>,
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Session recorded during the pick phase of a ranked draft.
+    const RECORDED_SESSION: &str = include_str!("../../tests/fixtures/champ_select_session.json");
+
+    #[test]
+    fn deserializes_recorded_session() {
+        let session = serde_json::from_str::<ChampSelectSession>(RECORDED_SESSION).unwrap();
+        assert_eq!(session.game_id, 6912345678);
+        assert_eq!(session.my_team.len(), 5);
+        assert_eq!(session.their_team.len(), 5);
+        assert_eq!(session.local_position(), "middle");
+        // no spell selected yet
+        assert_eq!(session.my_team[3].spell1_id, u64::MAX);
+        assert_eq!(session.my_team[3].spell2_id, 18446744073709551615);
+        assert_eq!(session.trades[1], SwapRequest { cell_id: 2, id: 4, state: "RECEIVED".to_string() });
+        assert_eq!(session.pick_order_swaps.len(), 4);
+        assert_eq!(session.bench_champions[1], BenchChampion { champion_id: 86, is_priority: true });
+        assert_eq!(session.chat_details.multi_user_chat_password.as_deref(), Some("3f9a0c1b7e"));
+        assert_eq!(session.chat_details.chat_room_name, None);
+        assert!(session.banned_champions().contains(&103));
+        assert_eq!(session.teammate_champions(), [51, 412, 51]);
+        // reported by both the teams and the completed actions
+        assert_eq!(session.picked_champions(), [122, 64, 122, 64]);
+        assert_eq!(session.next_action("pick"), None);
+        assert_eq!(session.action(14).map(|action| action.is_in_progress), Some(true));
+    }
+
+    #[test]
+    fn deserializes_legacy_session() {
+        let session = serde_json::from_value::<ChampSelectSession>(serde_json::json!({
+            "actions": [[
+                {"actorCellId": 0, "championId": 0, "completed": false, "id": 1, "isAllyAction": true, "isInProgress": true, "pickTurn": 1, "type": "ban"}
+            ]],
+            "bans": {"myTeamBans": [], "numBans": 6, "theirTeamBans": []},
+            "chatDetails": {"chatRoomName": "c1~0123456789abcdef", "chatRoomPassword": "pw"},
+            "gameId": 1,
+            "localPlayerCellId": 0,
+            "timer": {"adjustedTimeLeftInPhase": 30000, "internalNowInEpochMs": 0, "isInfinite": false, "phase": "BAN_PICK", "totalTimeInPhase": 30000}
+        })).unwrap();
+        assert!(session.my_team.is_empty());
+        assert!(session.their_team.is_empty());
+        assert!(session.trades.is_empty());
+        assert!(session.pick_order_swaps.is_empty());
+        assert!(session.bench_champions.is_empty());
+        assert_eq!(session.chat_details.chat_room_name.as_deref(), Some("c1~0123456789abcdef"));
+        assert_eq!(session.chat_details.multi_user_chat_id, None);
+        assert_eq!(session.local_position(), "");
+        assert_eq!(session.current_ban_action().map(|action| action.id), Some(1));
+    }
+
+    #[test]
+    fn finds_the_local_ban_after_a_completed_one() {
+        let session = |is_in_progress: bool| serde_json::from_value::<ChampSelectSession>(serde_json::json!({
+            "actions": [[
+                {"actorCellId": 0, "championId": 84, "completed": true, "id": 1, "isAllyAction": true, "isInProgress": false, "pickTurn": 1, "type": "ban"}
+            ], [
+                {"actorCellId": 0, "championId": 0, "completed": false, "id": 3, "isAllyAction": true, "isInProgress": is_in_progress, "pickTurn": 2, "type": "ban"}
+            ]],
+            "bans": {"myTeamBans": [84], "numBans": 2, "theirTeamBans": []},
+            "gameId": 1,
+            "localPlayerCellId": 0,
+            "timer": {"adjustedTimeLeftInPhase": 30000, "internalNowInEpochMs": 0, "isInfinite": false, "phase": "BAN_PICK", "totalTimeInPhase": 30000}
+        })).unwrap();
+        // before its turn, the second ban is the one to hover
+        assert_eq!(session(false).next_action("ban").map(|action| action.id), Some(3));
+        assert_eq!(session(false).current_action("ban"), None);
+        // on its turn, the second ban is the one to lock
+        assert_eq!(session(true).current_ban_action().map(|action| action.id), Some(3));
+        assert_eq!(session(true).next_action("ban").map(|action| action.id), Some(3));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,253 @@
+use std::path::{Path, PathBuf};
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{AppError, AppResult, ChampionId};
+use crate::client::LolClient;
+use crate::config::CONFIG_DIR_NAME;
+
+pub const CHAMPION_SUMMARY_ENDPOINT: &str = "/lol-game-data/assets/v1/champion-summary.json";
+pub const CHAMPION_CACHE_FILE_NAME: &str = "champion_summary.json";
+/// Names suggested for an unknown champion.
+pub const MAX_SUGGESTIONS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChampionSummary {
+    pub id: ChampionId,
+    pub name: String,
+    /// Internal name, e.g. `MonkeyKing` for Wukong.
+    pub alias: String,
+}
+
+
+/// Champions known by the client, with id, name and alias lookups.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChampionCatalog {
+    champions: Vec<ChampionSummary>,
+}
+
+
+impl ChampionCatalog {
+    pub fn new(champions: Vec<ChampionSummary>) -> Self {
+        // the summary contains a `-1` "None" entry
+        let mut champions = champions
+            .into_iter()
+            .filter(|champion| champion.id > 0)
+            .collect::<Vec<_>>();
+        champions.sort_by(|a, b| a.name.cmp(&b.name));
+        Self { champions }
+    }
+
+    /// In the platform cache directory, e.g. `%LOCALAPPDATA%\ban_ahri_client` or `~/.cache/ban_ahri_client`.
+    pub fn cache_path() -> PathBuf {
+        dirs::cache_dir()
+            .map(|cache_dir| cache_dir.join(CONFIG_DIR_NAME))
+            .unwrap_or_default()
+            .join(CHAMPION_CACHE_FILE_NAME)
+    }
+
+    pub async fn fetch(client: &LolClient) -> AppResult<Self> {
+        Ok(Self::new(client.get(CHAMPION_SUMMARY_ENDPOINT).await?))
+    }
+
+    /// Fetches the catalog from the client and caches it, falling back to the cached one.
+    pub async fn refresh(client: LolClient) -> AppResult<Self> {
+        match Self::fetch(&client).await {
+            Ok(catalog) => {
+                // the fetched catalog is still usable, it's only fetched again on the next start
+                if let Err(err) = catalog.save_cache(Self::cache_path()).await {
+                    println!("Failed to cache the champion list: {:?}", err);
+                }
+                Ok(catalog)
+            }
+            Err(err) => Self::load_cache(Self::cache_path()).map_err(|_| err),
+        }
+    }
+
+    pub fn load_cache(path: impl AsRef<Path>) -> AppResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map(Self::new)
+            .map_err(|err| AppError::InvalidResponse(err.to_string()))
+    }
+
+    pub async fn save_cache(&self, path: impl AsRef<Path>) -> AppResult<()> {
+        let content = serde_json::to_string(&self.champions)
+            .map_err(|err| AppError::IoError(err.to_string()))?;
+        if let Some(dir) = path.as_ref().parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        tokio::fs::write(path, content).await?;
+        Ok(())
+    }
+
+    pub fn champions(&self) -> &[ChampionSummary] {
+        &self.champions
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.champions.is_empty()
+    }
+
+    pub fn by_id(&self, champion_id: ChampionId) -> Option<&ChampionSummary> {
+        self.champions.iter().find(|champion| champion.id == champion_id)
+    }
+
+    /// Display name of a champion, its id if it isn't known.
+    pub fn name(&self, champion_id: ChampionId) -> String {
+        self.by_id(champion_id)
+            .map(|champion| champion.name.clone())
+            .unwrap_or_else(|| champion_id.to_string())
+    }
+
+    /// Exact name or alias lookup, ignoring case, accents and punctuation.
+    pub fn by_name(&self, name: &str) -> Option<&ChampionSummary> {
+        let name = normalize(name);
+        self.champions
+            .iter()
+            .find(|champion| normalize(&champion.name) == name || normalize(&champion.alias) == name)
+    }
+
+    /// Champions matching `query`, best match first: exact, prefix, substring then close typos.
+    /// Only meant for suggestions, partial input like `"a"` matches a real champion.
+    pub fn search(&self, query: &str) -> Vec<&ChampionSummary> {
+        let query = normalize(query);
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let mut matches = self.champions
+            .iter()
+            .filter_map(|champion| {
+                [&champion.name, &champion.alias]
+                    .into_iter()
+                    .filter_map(|name| match_score(&query, &normalize(name)))
+                    .min()
+                    .map(|score| (score, champion))
+            })
+            .collect::<Vec<_>>();
+        matches.sort_by_key(|(score, _)| *score);
+        matches.into_iter().map(|(_, champion)| champion).collect()
+    }
+
+    /// Names of the best [`Self::search`] matches, to suggest when `query` can't be resolved.
+    pub fn suggestions(&self, query: &str) -> Vec<String> {
+        self.search(query)
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|champion| champion.name.clone())
+            .collect()
+    }
+
+    /// Resolves a champion id, or an exact name or alias, to an id.
+    pub fn resolve(&self, query: &str) -> Option<ChampionId> {
+        if let Ok(champion_id) = query.trim().parse::<ChampionId>() {
+            return Some(champion_id);
+        }
+        self.by_name(query).map(|champion| champion.id)
+    }
+}
+
+
+/// Lower case ascii letters and digits only, accents are folded: `"Kai'Sa"` -> `"kaisa"`.
+pub fn normalize(name: &str) -> String {
+    name.chars()
+        .flat_map(char::to_lowercase)
+        .map(fold_accent)
+        .filter(char::is_ascii_alphanumeric)
+        .collect()
+}
+
+fn fold_accent(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+        'ç' => 'c',
+        'è' | 'é' | 'ê' | 'ë' => 'e',
+        'ì' | 'í' | 'î' | 'ï' => 'i',
+        'ñ' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => 'o',
+        'ù' | 'ú' | 'û' | 'ü' => 'u',
+        'ý' | 'ÿ' => 'y',
+        c => c,
+    }
+}
+
+/// Lower is better, `None` when `name` doesn't match `query` at all.
+fn match_score(query: &str, name: &str) -> Option<usize> {
+    if name == query {
+        Some(0)
+    } else if name.starts_with(query) {
+        Some(1)
+    } else if name.contains(query) {
+        Some(2)
+    } else {
+        let max_distance = query.len() / 4;
+        let distance = edit_distance(query, name);
+        (max_distance > 0 && distance <= max_distance).then_some(2 + distance)
+    }
+}
+
+/// Optimal string alignment distance, a swap of two adjacent letters counts as one edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut rows = vec![(0..=b.len()).collect::<Vec<_>>()];
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (rows[i - 1][j - 1] + cost)
+                .min(rows[i - 1][j] + 1)
+                .min(row[j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+        rows.push(row);
+    }
+    rows[a.len()][b.len()]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_champion_list;
+
+    fn catalog() -> ChampionCatalog {
+        ChampionCatalog::new(vec![
+            ChampionSummary { id: -1, name: "None".to_string(), alias: "None".to_string() },
+            ChampionSummary { id: 103, name: "Ahri".to_string(), alias: "Ahri".to_string() },
+            ChampionSummary { id: 84, name: "Akali".to_string(), alias: "Akali".to_string() },
+            ChampionSummary { id: 62, name: "Wukong".to_string(), alias: "MonkeyKing".to_string() },
+            ChampionSummary { id: 145, name: "Kai'Sa".to_string(), alias: "Kaisa".to_string() },
+        ])
+    }
+
+    #[test]
+    fn resolves_exact_names_aliases_and_ids() {
+        let catalog = catalog();
+        assert_eq!(catalog.resolve("Ahri"), Some(103));
+        assert_eq!(catalog.resolve(" wukong "), Some(62));
+        assert_eq!(catalog.resolve("MonkeyKing"), Some(62));
+        assert_eq!(catalog.resolve("kai sa"), Some(145));
+        assert_eq!(catalog.resolve("84"), Some(84));
+        assert_eq!(catalog.by_id(-1), None);
+    }
+
+    #[test]
+    fn partial_names_are_only_suggested() {
+        let catalog = catalog();
+        assert_eq!(catalog.resolve("a"), None);
+        assert_eq!(catalog.resolve("ahr"), None);
+        assert_eq!(catalog.resolve("akalli"), None);
+        assert_eq!(catalog.search("ahr").first().map(|champion| champion.id), Some(103));
+        assert_eq!(catalog.search("akalli").first().map(|champion| champion.id), Some(84));
+        assert_eq!(catalog.suggestions("akalli"), ["Akali"]);
+        assert!(catalog.suggestions("zzz").is_empty());
+        let err = AppError::UnknownChampion("akalli".to_string(), catalog.suggestions("akalli"));
+        assert_eq!(err.to_string(), "Unknown champion: \"akalli\", did you mean Akali?");
+        // typed one key at a time in the ban list
+        assert_eq!(parse_champion_list("Ahri, a", &catalog), None);
+        assert_eq!(parse_champion_list("Ahri, akali", &catalog), Some(vec![103, 84]));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,310 @@
+use std::path::{Path, PathBuf};
+
+use crate::client::{check_riot_path, lockfile_path};
+
+/// Written by the Riot client on Windows, maps each game install to the Riot client serving it.
+pub const RIOT_CLIENT_INSTALLS_FILE_NAME: &str = "RiotClientInstalls.json";
+pub const LEAGUE_DIR_NAME: &str = "League of Legends";
+/// Install directories relative to a drive root, Riot's default first.
+pub const WINDOWS_INSTALL_DIRS: [&str; 4] = ["Riot Games", "Program Files\\Riot Games", "Program Files (x86)\\Riot Games", "Games\\Riot Games"];
+
+/// Where a candidate was found, in ranking order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PathSource {
+    RiotClientInstalls,
+    Windows,
+    Wine,
+    Lutris,
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RiotPathCandidate {
+    /// Directory containing `League of Legends`, as expected by [`check_riot_path`].
+    pub path: String,
+    pub source: PathSource,
+    /// A lockfile is present, the client is most likely running from there.
+    pub is_running: bool,
+}
+
+impl std::fmt::Display for RiotPathCandidate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.path)
+    }
+}
+
+
+/// Looks for Riot installations in the usual Windows locations and in Wine prefixes.
+/// Roots are configurable so the scan can run against any directory tree.
+#[derive(Debug, Clone, Default)]
+pub struct RiotPathDiscovery {
+    /// Drive roots scanned for [`WINDOWS_INSTALL_DIRS`], e.g. `C:\`.
+    pub drives: Vec<PathBuf>,
+    /// Directory containing `Riot Games\RiotClientInstalls.json`, `C:\ProgramData` on Windows.
+    pub program_data: Option<PathBuf>,
+    /// Home directory, for `~/.wine`, `~/Games/*` and the Lutris game configs.
+    pub home: Option<PathBuf>,
+}
+
+
+impl RiotPathDiscovery {
+    /// Roots of the current machine: drives and `ProgramData` on Windows, the home directory elsewhere.
+    pub fn from_env() -> Self {
+        if cfg!(windows) {
+            Self {
+                drives: ('C'..='Z')
+                    .map(|letter| PathBuf::from(format!("{}:\\", letter)))
+                    .filter(|drive| drive.exists())
+                    .collect(),
+                program_data: std::env::var_os("ProgramData").map(PathBuf::from),
+                home: None,
+            }
+        } else {
+            Self {
+                home: dirs::home_dir(),
+                ..Default::default()
+            }
+        }
+    }
+
+    /// Existing installations, running clients first, then by [`PathSource`].
+    pub fn discover(&self) -> Vec<RiotPathCandidate> {
+        let mut paths = Vec::new();
+        if let Some(program_data) = &self.program_data {
+            for path in read_riot_client_installs(program_data, |path| Some(PathBuf::from(path))) {
+                paths.push((path, PathSource::RiotClientInstalls));
+            }
+        }
+        for drive in &self.drives {
+            for dir in WINDOWS_INSTALL_DIRS {
+                paths.push((drive.join(dir), PathSource::Windows));
+            }
+        }
+        if let Some(home) = &self.home {
+            for prefix in wine_prefixes(home) {
+                paths.extend(scan_wine_prefix(&prefix).into_iter().map(|path| (path, PathSource::Wine)));
+            }
+            for prefix in lutris_prefixes(home) {
+                paths.extend(scan_wine_prefix(&prefix).into_iter().map(|path| (path, PathSource::Lutris)));
+            }
+        }
+
+        let mut candidates: Vec<RiotPathCandidate> = Vec::new();
+        for (path, source) in paths {
+            let path = path.to_string_lossy().trim_end_matches(['/', '\\']).to_string();
+            if !check_riot_path(path.clone()) || candidates.iter().any(|candidate| candidate.path == path) {
+                continue;
+            }
+            candidates.push(RiotPathCandidate {
+                is_running: lockfile_path(path.clone()).exists(),
+                path,
+                source,
+            });
+        }
+        // stable, so candidates of the same rank keep the scan order
+        candidates.sort_by_key(|candidate| (!candidate.is_running, candidate.source));
+        candidates
+    }
+}
+
+
+/// Ranked Riot installations of this machine.
+pub fn discover_riot_paths() -> Vec<RiotPathCandidate> {
+    RiotPathDiscovery::from_env().discover()
+}
+
+
+/// Riot paths listed in `<program_data>/Riot Games/RiotClientInstalls.json`, the parents of its
+/// `League of Legends` installs. `map_path` turns the Windows paths of the file into local ones.
+fn read_riot_client_installs(program_data: &Path, map_path: impl Fn(&str) -> Option<PathBuf>) -> Vec<PathBuf> {
+    let installs = std::fs::read_to_string(program_data.join("Riot Games").join(RIOT_CLIENT_INSTALLS_FILE_NAME))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+    let Some(associated_client) = installs.as_ref().and_then(|installs| installs["associated_client"].as_object()) else {
+        return Vec::new();
+    };
+    associated_client
+        .keys()
+        .filter_map(|game_path| {
+            let game_path = game_path.trim_end_matches(['/', '\\']);
+            let (riot_path, game_dir) = game_path.rsplit_once(['/', '\\'])?;
+            (game_dir == LEAGUE_DIR_NAME).then(|| map_path(riot_path)).flatten()
+        })
+        .collect()
+}
+
+/// `~/.wine` and every `~/Games/*` directory which looks like a prefix.
+fn wine_prefixes(home: &Path) -> Vec<PathBuf> {
+    let mut prefixes = vec![home.join(".wine")];
+    if let Ok(entries) = std::fs::read_dir(home.join("Games")) {
+        let mut games = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect::<Vec<_>>();
+        games.sort();
+        prefixes.extend(games);
+    }
+    prefixes.retain(|prefix| prefix.join("drive_c").is_dir());
+    prefixes
+}
+
+/// `prefix:` entries of the Lutris game configs.
+fn lutris_prefixes(home: &Path) -> Vec<PathBuf> {
+    let config_dirs = [home.join(".config").join("lutris").join("games"), home.join(".local").join("share").join("lutris").join("games")];
+    let mut configs = config_dirs
+        .iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "yml"))
+        .collect::<Vec<_>>();
+    configs.sort();
+    configs
+        .iter()
+        .filter_map(|config| std::fs::read_to_string(config).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .filter_map(|line| line.trim().strip_prefix("prefix:"))
+                .map(|prefix| expand_home(prefix.trim().trim_matches(['"', '\'']), home))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn expand_home(path: &str, home: &Path) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(path) => home.join(path),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Riot paths inside a Wine prefix: the ones of its `RiotClientInstalls.json`, then the default `C:\Riot Games`.
+fn scan_wine_prefix(prefix: &Path) -> Vec<PathBuf> {
+    let drive_c = prefix.join("drive_c");
+    let mut paths = read_riot_client_installs(&drive_c.join("ProgramData"), |path| wine_path(prefix, path));
+    paths.push(drive_c.join("Riot Games"));
+    paths
+}
+
+/// Maps a Windows path of a prefix, e.g. `C:/Riot Games`, to the host one.
+fn wine_path(prefix: &Path, windows_path: &str) -> Option<PathBuf> {
+    let (drive, path) = windows_path.split_once(':')?;
+    let drive = drive.to_lowercase();
+    let mut host_path = if drive == "c" {
+        prefix.join("drive_c")
+    } else {
+        prefix.join("dosdevices").join(format!("{}:", drive))
+    };
+    for component in path.split(['/', '\\']).filter(|component| !component.is_empty()) {
+        host_path.push(component);
+    }
+    Some(host_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    /// Creates a League install in `riot_path`, relative to `root`.
+    fn install(root: &TempDir, riot_path: &str) -> String {
+        root.write(Path::new(riot_path).join(LEAGUE_DIR_NAME).join("LeagueClient.exe"), "");
+        root.path().join(riot_path).to_string_lossy().to_string()
+    }
+
+    fn discover_home(root: &TempDir) -> Vec<RiotPathCandidate> {
+        RiotPathDiscovery {
+            home: Some(root.path().to_path_buf()),
+            ..Default::default()
+        }.discover()
+    }
+
+    fn paths(candidates: &[RiotPathCandidate]) -> Vec<(&str, PathSource)> {
+        candidates.iter().map(|candidate| (candidate.path.as_str(), candidate.source)).collect()
+    }
+
+    #[test]
+    fn reads_riot_client_installs() {
+        let root = TempDir::new();
+        let riot_path = install(&root, "Custom/Riot Games");
+        let installs = serde_json::json!({
+            "associated_client": {
+                format!("{}/{}/", riot_path, LEAGUE_DIR_NAME): "C:/Riot Games/Riot Client/RiotClientServices.exe",
+                format!("{}/VALORANT/live/", riot_path): "C:/Riot Games/Riot Client/RiotClientServices.exe",
+            },
+            "rc_default": "C:/Riot Games/Riot Client/RiotClientServices.exe"
+        });
+        root.write(Path::new("ProgramData/Riot Games").join(RIOT_CLIENT_INSTALLS_FILE_NAME), installs.to_string());
+        let candidates = RiotPathDiscovery {
+            program_data: Some(root.path().join("ProgramData")),
+            ..Default::default()
+        }.discover();
+        assert_eq!(paths(&candidates), [(riot_path.as_str(), PathSource::RiotClientInstalls)]);
+        assert!(!candidates[0].is_running);
+    }
+
+    #[test]
+    fn finds_default_wine_prefix() {
+        let root = TempDir::new();
+        let riot_path = install(&root, ".wine/drive_c/Riot Games");
+        assert_eq!(paths(&discover_home(&root)), [(riot_path.as_str(), PathSource::Wine)]);
+    }
+
+    #[test]
+    fn finds_prefixes_in_games() {
+        let root = TempDir::new();
+        let default_path = install(&root, "Games/league-of-legends/drive_c/Riot Games");
+        // moved to another directory of the prefix, only known from its RiotClientInstalls.json
+        let moved_path = install(&root, "Games/lol/drive_c/Games/Riot");
+        let installs = serde_json::json!({"associated_client": {"C:\\Games\\Riot\\League of Legends\\": ""}});
+        root.write(Path::new("Games/lol/drive_c/ProgramData/Riot Games").join(RIOT_CLIENT_INSTALLS_FILE_NAME), installs.to_string());
+        // not a prefix
+        install(&root, "Games/notes/Riot Games");
+        assert_eq!(paths(&discover_home(&root)), [
+            (default_path.as_str(), PathSource::Wine),
+            (moved_path.as_str(), PathSource::Wine),
+        ]);
+    }
+
+    #[test]
+    fn finds_lutris_prefixes() {
+        let root = TempDir::new();
+        let riot_path = install(&root, "lutris/league/drive_c/Riot Games");
+        root.write(".config/lutris/games/league-of-legends-1718040123.yml", "\
+game:
+  exe: drive_c/Riot Games/Riot Client/RiotClientServices.exe
+  prefix: \"~/lutris/league\"
+system:
+  disable_runtime: true
+");
+        assert_eq!(paths(&discover_home(&root)), [(riot_path.as_str(), PathSource::Lutris)]);
+    }
+
+    #[test]
+    fn ranks_running_clients_first_then_by_source() {
+        let root = TempDir::new();
+        let wine_path = install(&root, ".wine/drive_c/Riot Games");
+        let lutris_path = install(&root, "lutris/league/drive_c/Riot Games");
+        let running_path = install(&root, "Games/running/drive_c/Riot Games");
+        root.write(".local/share/lutris/games/league.yml", "game:\n  prefix: ~/lutris/league\n");
+        // also listed by Lutris, the first source wins
+        root.write(".config/lutris/games/wine.yml", format!("game:\n  prefix: {}\n", root.path().join(".wine").display()));
+        root.write(Path::new(&running_path).join(LEAGUE_DIR_NAME).join("lockfile"), "LeagueClient:1:2999:pw:https");
+
+        let candidates = discover_home(&root);
+        assert_eq!(paths(&candidates), [
+            (running_path.as_str(), PathSource::Wine),
+            (wine_path.as_str(), PathSource::Wine),
+            (lutris_path.as_str(), PathSource::Lutris),
+        ]);
+        assert_eq!(candidates.iter().map(|candidate| candidate.is_running).collect::<Vec<_>>(), [true, false, false]);
+    }
+
+    #[test]
+    fn maps_wine_paths() {
+        let prefix = Path::new("/home/user/.wine");
+        assert_eq!(wine_path(prefix, "C:/Riot Games"), Some(prefix.join("drive_c").join("Riot Games")));
+        assert_eq!(wine_path(prefix, "D:\\Games\\Riot"), Some(prefix.join("dosdevices").join("d:").join("Games").join("Riot")));
+        assert_eq!(wine_path(prefix, "Riot Games"), None);
+    }
+}
\ No newline at end of file
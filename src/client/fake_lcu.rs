@@ -0,0 +1,139 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::{AppError, AppResult, ChampionId};
+use crate::client::ActionMode;
+use crate::client::api::LcuApi;
+use crate::client::champ_select_session::ChampSelectSession;
+use crate::client::gameflow::{GameflowPhase, GameflowSession};
+use crate::client::ready_check::{ReadyCheck, ReadyCheckResponse};
+use crate::errors::LcuErrorResponse;
+
+/// An action update received by the fake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActionUpdate {
+    pub champion_id: ChampionId,
+    pub action_id: i64,
+    pub mode: ActionMode,
+}
+
+
+/// What the fake answers, and what it received.
+#[derive(Debug, Clone, Default)]
+pub struct FakeState {
+    pub phase: GameflowPhase,
+    pub gameflow_session: Option<GameflowSession>,
+    /// Returned by successive session reads, the last one is repeated. Empty outside of champ select.
+    pub sessions: VecDeque<ChampSelectSession>,
+    pub bannable_champions: Vec<ChampionId>,
+    pub pickable_champions: Vec<ChampionId>,
+    /// Chat session, e.g. `{"availability": "away"}`. `None` while the chat service is down.
+    pub user_session: Option<serde_json::Value>,
+    pub ready_check: Option<ReadyCheck>,
+    /// Returned by the next action updates instead of accepting them, e.g. an `RpcError` of a busy client.
+    pub update_errors: VecDeque<AppError>,
+    pub action_updates: Vec<ActionUpdate>,
+    pub ready_check_responses: Vec<ReadyCheckResponse>,
+}
+
+
+/// In-memory [`LcuApi`] answering scripted sessions, to run the ban and pick logic without a League client.
+/// Action updates are recorded but don't change the sessions, the script decides what the client does.
+#[derive(Debug, Clone, Default)]
+pub struct FakeLcu {
+    state: Arc<Mutex<FakeState>>,
+}
+
+
+impl FakeLcu {
+    pub fn new(state: FakeState) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// In champ select, answering `sessions` in order.
+    pub fn with_sessions(sessions: impl IntoIterator<Item = ChampSelectSession>) -> Self {
+        Self::new(FakeState {
+            phase: GameflowPhase::ChampSelect,
+            sessions: sessions.into_iter().collect(),
+            ..Default::default()
+        })
+    }
+
+    /// Shared with the clones, so the script can be changed while the logic runs.
+    pub fn state(&self) -> MutexGuard<'_, FakeState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn answer_ready_check(&self, response: ReadyCheckResponse) -> AppResult<()> {
+        let mut state = self.state();
+        let Some(ready_check) = state.ready_check.as_mut() else {
+            return Err(not_found("Not in a ready check"));
+        };
+        ready_check.player_response = format!("{:?}", response);
+        state.ready_check_responses.push(response);
+        Ok(())
+    }
+}
+
+
+impl LcuApi for FakeLcu {
+    async fn get_champ_select_session(&self) -> AppResult<ChampSelectSession> {
+        let mut state = self.state();
+        if state.sessions.len() > 1 {
+            return Ok(state.sessions.pop_front().unwrap_or_default());
+        }
+        state.sessions.front().cloned().ok_or_else(|| not_found("No active delegate"))
+    }
+
+    async fn get_bannable_champion_ids(&self) -> AppResult<Vec<ChampionId>> {
+        Ok(self.state().bannable_champions.clone())
+    }
+
+    async fn get_pickable_champion_ids(&self) -> AppResult<Vec<ChampionId>> {
+        Ok(self.state().pickable_champions.clone())
+    }
+
+    async fn update_action(&self, champion_id: ChampionId, action_id: i64, mode: ActionMode) -> AppResult<()> {
+        let mut state = self.state();
+        state.action_updates.push(ActionUpdate { champion_id, action_id, mode });
+        match state.update_errors.pop_front() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    async fn get_gameflow_phase(&self) -> AppResult<GameflowPhase> {
+        Ok(self.state().phase)
+    }
+
+    async fn get_gameflow_session(&self) -> AppResult<GameflowSession> {
+        self.state().gameflow_session.clone().ok_or_else(|| not_found("No gameflow session"))
+    }
+
+    async fn get_user_session(&self) -> AppResult<serde_json::Value> {
+        self.state().user_session.clone().ok_or_else(|| not_found("Chat service unavailable"))
+    }
+
+    async fn get_ready_check(&self) -> AppResult<ReadyCheck> {
+        self.state().ready_check.clone().ok_or_else(|| not_found("Not in a ready check"))
+    }
+
+    async fn accept_ready_check(&self) -> AppResult<()> {
+        self.answer_ready_check(ReadyCheckResponse::Accepted)
+    }
+
+    async fn decline_ready_check(&self) -> AppResult<()> {
+        self.answer_ready_check(ReadyCheckResponse::Declined)
+    }
+}
+
+
+fn not_found(message: &str) -> AppError {
+    AppError::NotFound(LcuErrorResponse {
+        error_code: "RPC_ERROR".to_string(),
+        http_status: 404,
+        message: message.to_string(),
+    })
+}
\ No newline at end of file
@@ -0,0 +1,234 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{AppError, AppResult};
+use crate::client::LolClient;
+use crate::client::api::LcuApi;
+use crate::client::websocket::{LcuEvent, LcuWebSocket};
+
+pub const GAMEFLOW_PHASE_ENDPOINT: &str = "/lol-gameflow/v1/gameflow-phase";
+pub const GAMEFLOW_SESSION_ENDPOINT: &str = "/lol-gameflow/v1/session";
+pub const GAMEFLOW_PHASE_EVENT: &str = "OnJsonApiEvent_lol-gameflow_v1_gameflow-phase";
+pub const GAMEFLOW_SESSION_EVENT: &str = "OnJsonApiEvent_lol-gameflow_v1_session";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum GameflowPhase {
+    #[default]
+    None,
+    Lobby,
+    Matchmaking,
+    CheckedIntoTournament,
+    ReadyCheck,
+    ChampSelect,
+    GameStart,
+    FailedToLaunch,
+    InProgress,
+    Reconnect,
+    WaitingForStats,
+    PreEndOfGame,
+    EndOfGame,
+    TerminatedInError,
+    /// A phase added by a newer client.
+    #[serde(other)]
+    Unknown,
+}
+
+impl std::fmt::Display for GameflowPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameflowSession {
+    #[serde(rename = "gameData")]
+    pub game_data: GameData,
+    pub phase: GameflowPhase,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameData {
+    #[serde(rename = "gameId")]
+    pub game_id: i64,
+    #[serde(rename = "isCustomGame")]
+    pub is_custom_game: bool,
+    pub queue: Queue,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Queue {
+    pub id: i64,
+    #[serde(rename = "gameMode")]
+    pub game_mode: String,
+    #[serde(rename = "type")]
+    pub type_field: String,
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhaseTransition {
+    pub from: GameflowPhase,
+    pub to: GameflowPhase,
+}
+
+
+impl LolClient {
+    pub async fn get_gameflow_phase(&self) -> AppResult<GameflowPhase> {
+        self.get(GAMEFLOW_PHASE_ENDPOINT).await
+    }
+
+    pub async fn get_gameflow_session(&self) -> AppResult<GameflowSession> {
+        self.get(GAMEFLOW_SESSION_ENDPOINT).await
+    }
+}
+
+
+/// Follows the client's gameflow phase and session, reporting each phase change once.
+#[derive(Debug, Clone, Default)]
+pub struct GameflowTracker {
+    phase: GameflowPhase,
+    session: Option<GameflowSession>,
+}
+
+
+impl GameflowTracker {
+    pub fn phase(&self) -> GameflowPhase {
+        self.phase
+    }
+
+    pub fn session(&self) -> Option<&GameflowSession> {
+        self.session.as_ref()
+    }
+
+    /// Moves to `phase`, `None` if it is the current one.
+    pub fn update(&mut self, phase: GameflowPhase) -> Option<PhaseTransition> {
+        if phase == self.phase {
+            return None;
+        }
+        let transition = PhaseTransition { from: self.phase, to: phase };
+        self.phase = phase;
+        Some(transition)
+    }
+
+    /// Applies a gameflow websocket event, other events are ignored.
+    pub fn apply_event(&mut self, event: LcuEvent) -> Option<PhaseTransition> {
+        match event.uri.as_str() {
+            GAMEFLOW_PHASE_ENDPOINT | GAMEFLOW_SESSION_ENDPOINT if event.event_type == "Delete" => {
+                self.session = None;
+                self.update(GameflowPhase::None)
+            }
+            GAMEFLOW_PHASE_ENDPOINT => self.update(serde_json::from_value(event.data).ok()?),
+            GAMEFLOW_SESSION_ENDPOINT => {
+                let session = serde_json::from_value::<GameflowSession>(event.data).ok()?;
+                let phase = session.phase;
+                self.session = Some(session);
+                self.update(phase)
+            }
+            _ => None,
+        }
+    }
+
+    /// Reads the phase and session from the client.
+    pub async fn sync(&mut self, client: &impl LcuApi) -> AppResult<Option<PhaseTransition>> {
+        let phase = client.get_gameflow_phase().await?;
+        // there is no session outside of a lobby
+        self.session = client.get_gameflow_session().await.ok();
+        Ok(self.update(phase))
+    }
+
+    pub async fn subscribe(socket: &mut LcuWebSocket) -> AppResult<()> {
+        socket.subscribe(GAMEFLOW_PHASE_EVENT).await?;
+        socket.subscribe(GAMEFLOW_SESSION_EVENT).await
+    }
+
+    /// Waits for the next phase change on a socket set up with [`GameflowTracker::subscribe`].
+    pub async fn next_transition(&mut self, socket: &mut LcuWebSocket) -> AppResult<PhaseTransition> {
+        while let Some(event) = socket.next_event().await? {
+            if let Some(transition) = self.apply_event(event) {
+                return Ok(transition);
+            }
+        }
+        Err(AppError::RiotClientError("Websocket closed".to_string()))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::fake_lcu::{FakeLcu, FakeState};
+
+    fn event(uri: &str, event_type: &str, data: serde_json::Value) -> LcuEvent {
+        LcuEvent {
+            data,
+            event_type: event_type.to_string(),
+            uri: uri.to_string(),
+        }
+    }
+
+    fn session(game_id: i64, phase: GameflowPhase) -> GameflowSession {
+        GameflowSession {
+            game_data: GameData { game_id, ..Default::default() },
+            phase,
+        }
+    }
+
+    fn transition(from: GameflowPhase, to: GameflowPhase) -> Option<PhaseTransition> {
+        Some(PhaseTransition { from, to })
+    }
+
+    #[test]
+    fn phase_events_move_to_the_phase() {
+        let mut tracker = GameflowTracker::default();
+        let lobby = event(GAMEFLOW_PHASE_ENDPOINT, "Update", serde_json::json!("Lobby"));
+        assert_eq!(tracker.apply_event(lobby.clone()), transition(GameflowPhase::None, GameflowPhase::Lobby));
+        assert_eq!(tracker.phase(), GameflowPhase::Lobby);
+        // reported once
+        assert_eq!(tracker.apply_event(lobby), None);
+        assert_eq!(tracker.update(GameflowPhase::Lobby), None);
+        // other endpoints and unreadable data are ignored
+        assert_eq!(tracker.apply_event(event("/lol-champ-select/v1/session", "Update", serde_json::json!("ChampSelect"))), None);
+        assert_eq!(tracker.apply_event(event(GAMEFLOW_PHASE_ENDPOINT, "Update", serde_json::json!(42))), None);
+        assert_eq!(tracker.phase(), GameflowPhase::Lobby);
+    }
+
+    #[test]
+    fn session_events_keep_the_session() {
+        let mut tracker = GameflowTracker::default();
+        let data = serde_json::to_value(session(42, GameflowPhase::ChampSelect)).unwrap();
+        let transition_to_champ_select = tracker.apply_event(event(GAMEFLOW_SESSION_ENDPOINT, "Update", data));
+        assert_eq!(transition_to_champ_select, transition(GameflowPhase::None, GameflowPhase::ChampSelect));
+        assert_eq!(tracker.session().map(|session| session.game_data.game_id), Some(42));
+    }
+
+    #[test]
+    fn delete_events_clear_the_session() {
+        let mut tracker = GameflowTracker::default();
+        let data = serde_json::to_value(session(42, GameflowPhase::InProgress)).unwrap();
+        tracker.apply_event(event(GAMEFLOW_SESSION_ENDPOINT, "Create", data));
+        let transition_to_none = tracker.apply_event(event(GAMEFLOW_SESSION_ENDPOINT, "Delete", serde_json::Value::Null));
+        assert_eq!(transition_to_none, transition(GameflowPhase::InProgress, GameflowPhase::None));
+        assert_eq!(tracker.session(), None);
+    }
+
+    #[tokio::test]
+    async fn sync_reads_the_client() {
+        let client = FakeLcu::new(FakeState {
+            phase: GameflowPhase::ReadyCheck,
+            gameflow_session: Some(session(7, GameflowPhase::ReadyCheck)),
+            ..Default::default()
+        });
+        let mut tracker = GameflowTracker::default();
+        assert_eq!(tracker.sync(&client).await, Ok(transition(GameflowPhase::None, GameflowPhase::ReadyCheck)));
+        assert_eq!(tracker.session().map(|session| session.game_data.game_id), Some(7));
+        assert_eq!(tracker.sync(&client).await, Ok(None));
+
+        // back in the main menu, there is no session
+        *client.state() = FakeState::default();
+        assert_eq!(tracker.sync(&client).await, Ok(transition(GameflowPhase::ReadyCheck, GameflowPhase::None)));
+        assert_eq!(tracker.session(), None);
+    }
+}
\ No newline at end of file
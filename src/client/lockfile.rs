@@ -0,0 +1,134 @@
+use std::net::{SocketAddr, TcpStream};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::{AppError, AppResult};
+
+pub const LISTENING_TIMEOUT_MILLIS: u64 = 200;
+
+/// Content of the `League of Legends/lockfile` written by a running client:
+/// `<process name>:<pid>:<port>:<password>:<protocol>`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Lockfile {
+    pub process_name: String,
+    pub pid: u32,
+    pub port: u16,
+    pub password: String,
+    pub protocol: String,
+}
+
+
+impl Lockfile {
+    pub fn read(path: impl AsRef<Path>) -> AppResult<Self> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    /// Whether something accepts connections on the port, a lockfile left by a crashed client doesn't.
+    pub fn is_listening(&self) -> bool {
+        let address = SocketAddr::from(([127, 0, 0, 1], self.port));
+        TcpStream::connect_timeout(&address, Duration::from_millis(LISTENING_TIMEOUT_MILLIS)).is_ok()
+    }
+}
+
+
+/// The lockfile content, as parsed by [`FromStr`].
+impl std::fmt::Display for Lockfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}:{}:{}", self.process_name, self.pid, self.port, self.password, self.protocol)
+    }
+}
+
+
+impl FromStr for Lockfile {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(AppError::LockfileEmpty);
+        }
+        let mut parts = s.split(':');
+        let mut next_part = || parts.next().unwrap_or_default().to_string();
+
+        let process_name = next_part();
+        if process_name.is_empty() {
+            return Err(AppError::LockfileInvalidProcessName(process_name));
+        }
+        let pid = next_part();
+        let pid = pid.parse().map_err(|_| AppError::LockfileInvalidPid(pid))?;
+        let port = next_part();
+        let port = match port.parse() {
+            Ok(port) if port != 0 => port,
+            _ => return Err(AppError::LockfileInvalidPort(port)),
+        };
+        let password = next_part();
+        if password.is_empty() {
+            return Err(AppError::LockfileInvalidPassword(password));
+        }
+        let protocol = next_part();
+        if protocol != "https" && protocol != "http" {
+            return Err(AppError::LockfileInvalidProtocol(protocol));
+        }
+        let extra_fields = parts.collect::<Vec<_>>();
+        if !extra_fields.is_empty() {
+            return Err(AppError::LockfileExtraFields(extra_fields.join(":")));
+        }
+
+        Ok(Self {
+            process_name,
+            pid,
+            port,
+            password,
+            protocol,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_lockfile() {
+        assert_eq!("".parse::<Lockfile>(), Err(AppError::LockfileEmpty));
+        assert_eq!(" \n".parse::<Lockfile>(), Err(AppError::LockfileEmpty));
+    }
+
+    #[test]
+    fn partial_lockfile() {
+        assert_eq!("LeagueClient:1".parse::<Lockfile>(), Err(AppError::LockfileInvalidPort(String::new())));
+        assert_eq!("LeagueClient:1:8080".parse::<Lockfile>(), Err(AppError::LockfileInvalidPassword(String::new())));
+        assert_eq!(":1:8080:pw:https".parse::<Lockfile>(), Err(AppError::LockfileInvalidProcessName(String::new())));
+        assert_eq!("LeagueClient:pid:8080:pw:https".parse::<Lockfile>(), Err(AppError::LockfileInvalidPid("pid".to_string())));
+    }
+
+    #[test]
+    fn zero_port() {
+        assert_eq!("LeagueClient:1:0:pw:https".parse::<Lockfile>(), Err(AppError::LockfileInvalidPort("0".to_string())));
+    }
+
+    #[test]
+    fn invalid_protocol() {
+        assert_eq!("LeagueClient:1:8080:pw:ftp".parse::<Lockfile>(), Err(AppError::LockfileInvalidProtocol("ftp".to_string())));
+    }
+
+    #[test]
+    fn extra_fields() {
+        assert_eq!("a:1:2:pw:https:extra".parse::<Lockfile>(), Err(AppError::LockfileExtraFields("extra".to_string())));
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let lockfile = "LeagueClient:12345:54321:p4ss-w0rd_:https\n".parse::<Lockfile>().unwrap();
+        assert_eq!(lockfile, Lockfile {
+            process_name: "LeagueClient".to_string(),
+            pid: 12345,
+            port: 54321,
+            password: "p4ss-w0rd_".to_string(),
+            protocol: "https".to_string(),
+        });
+        assert_eq!(lockfile.to_string().parse::<Lockfile>(), Ok(lockfile));
+    }
+}
\ No newline at end of file
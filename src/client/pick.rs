@@ -0,0 +1,271 @@
+use std::collections::BTreeMap;
+
+use serde_derive::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::{AppError, AppResult, ChampionId, wait_n_millis};
+use crate::client::{ActionMode, BAN_AHRI_THREAD_RETRY_MILLIS, LolClient};
+use crate::client::api::LcuApi;
+use crate::client::champ_select_session::ChampSelectSession;
+use crate::client::websocket::{CHAMP_SELECT_SESSION_EVENT, LcuWebSocket};
+
+pub const PICKABLE_CHAMPIONS_ENDPOINT: &str = "/lol-champ-select/v1/pickable-champion-ids";
+
+/// Assigned position in champ select, `Any` is used when there is none (blind pick) and as fallback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Position {
+    #[default]
+    Any,
+    Top,
+    Jungle,
+    Middle,
+    Bottom,
+    Utility,
+}
+
+impl Position {
+    pub const ALL: [Position; 6] = [Position::Any, Position::Top, Position::Jungle, Position::Middle, Position::Bottom, Position::Utility];
+
+    /// Parses the session's `assignedPosition`.
+    pub fn from_assigned(assigned_position: &str) -> Self {
+        match assigned_position.to_lowercase().as_str() {
+            "top" => Position::Top,
+            "jungle" => Position::Jungle,
+            "middle" => Position::Middle,
+            "bottom" => Position::Bottom,
+            "utility" => Position::Utility,
+            _ => Position::Any,
+        }
+    }
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+
+/// Champions to pick per position, highest priority first.
+pub type PickPreferences = BTreeMap<Position, Vec<ChampionId>>;
+
+/// Candidates for `position`, followed by the [`Position::Any`] ones.
+pub fn pick_candidates(preferences: &PickPreferences, position: Position) -> Vec<ChampionId> {
+    let mut candidates = preferences.get(&position).cloned().unwrap_or_default();
+    if position != Position::Any {
+        for &champion_id in preferences.get(&Position::Any).into_iter().flatten() {
+            if !candidates.contains(&champion_id) {
+                candidates.push(champion_id);
+            }
+        }
+    }
+    candidates
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PickOutcome {
+    pub champion_id: ChampionId,
+    pub mode: ActionMode,
+}
+
+
+impl LolClient {
+    pub async fn get_pickable_champion_ids(&self) -> AppResult<Vec<ChampionId>> {
+        self.get(PICKABLE_CHAMPIONS_ENDPOINT).await
+    }
+}
+
+
+/// Locks the preferred pick on the local player's pick turn, hovers it before that.
+pub async fn pick_in_session(client: &impl LcuApi, champ_select_session: &ChampSelectSession, preferences: &PickPreferences) -> AppResult<PickOutcome> {
+    let pickable_champions = client.get_pickable_champion_ids().await?;
+    pick_with_champions(client, champ_select_session, preferences, &pickable_champions).await
+}
+
+
+/// Like [`pick_in_session`] with the pickable champions of the session already known.
+pub async fn pick_with_champions(client: &impl LcuApi, champ_select_session: &ChampSelectSession, preferences: &PickPreferences, pickable_champions: &[ChampionId]) -> AppResult<PickOutcome> {
+    let (action, mode) = match champ_select_session.current_action("pick") {
+        Some(action) => (action, ActionMode::LockIn),
+        None => (
+            champ_select_session.next_action("pick")
+                .ok_or_else(|| AppError::RiotClientError("No pick action left".to_string()))?,
+            ActionMode::Hover,
+        ),
+    };
+    let position = Position::from_assigned(champ_select_session.local_position());
+    let (champion_id, skipped) = champ_select_session.choose_pick(&pick_candidates(preferences, position), pickable_champions);
+    for (skipped_champion_id, reason) in &skipped {
+        println!("Skipped pick {}: {}", skipped_champion_id, reason);
+    }
+    let champion_id = champion_id.ok_or(AppError::NoChampionToPick(skipped))?;
+    if mode == ActionMode::Hover && action.champion_id == champion_id as i64 {
+        return Ok(PickOutcome { champion_id, mode });
+    }
+    client.pick_champion(champion_id, action.id, mode).await?;
+    println!("Champion {} {:?} for {}", champion_id, mode, position);
+    Ok(PickOutcome { champion_id, mode })
+}
+
+
+/// Listens to champ select updates, keeps the preferred pick hovered and locks it on the local pick turn.
+/// The pickable champions are fetched once per game, not on every update.
+pub async fn auto_pick(client: &impl LcuApi, socket: &mut LcuWebSocket, preferences: &PickPreferences) -> AppResult<ChampionId> {
+    socket.subscribe(CHAMP_SELECT_SESSION_EVENT).await?;
+    let mut pickable_champions: Option<(i64, Vec<ChampionId>)> = None;
+    while let Some(session) = socket.next_champ_select_session().await? {
+        if session.is_spectating || session.next_action("pick").is_none() {
+            continue;
+        }
+        if pickable_champions.as_ref().map(|(game_id, _)| *game_id) != Some(session.game_id) {
+            match client.get_pickable_champion_ids().await {
+                Ok(champion_ids) => pickable_champions = Some((session.game_id, champion_ids)),
+                Err(err) => {
+                    println!("Failed to load pickable champions: {:?}", err);
+                    continue;
+                }
+            }
+        }
+        let champion_ids = pickable_champions.as_ref().map(|(_, champion_ids)| champion_ids.as_slice()).unwrap_or_default();
+        match pick_with_champions(client, &session, preferences, champion_ids).await {
+            Ok(outcome) if outcome.mode == ActionMode::LockIn => return Ok(outcome.champion_id),
+            Ok(_) => {}
+            Err(err) => println!("Failed to pick: {:?}", err),
+        }
+    }
+    Err(AppError::RiotClientError("Websocket closed".to_string()))
+}
+
+
+/// Keeps auto-picking in every champ select of the client in `riot_path` until a stop is received on `receiver`.
+pub async fn pick_thread(riot_path: String, preferences: PickPreferences, mut receiver: mpsc::Receiver<bool>) -> AppResult<()> {
+    loop {
+        tokio::select! {
+            _ = receiver.recv() => return Ok(()),
+            result = async {
+                let client = LolClient::new(riot_path.clone()).await?;
+                let mut socket = LcuWebSocket::connect(&client).await?;
+                auto_pick(&client, &mut socket, &preferences).await
+            } => match result {
+                Ok(champion_id) => println!("Picked champion {}", champion_id),
+                Err(err) => println!("Auto pick failed, retrying: {:?}", err),
+            },
+        }
+        tokio::select! {
+            _ = receiver.recv() => return Ok(()),
+            _ = wait_n_millis(BAN_AHRI_THREAD_RETRY_MILLIS) => {}
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::AHRI_ID;
+    use crate::client::gameflow::GameflowPhase;
+    use crate::client::websocket::CHAMP_SELECT_SESSION_EVENT;
+    use crate::client::CHAMP_SELECT_SESSION_ENDPOINT;
+    use crate::client::champ_select_session::SkipReason;
+    use crate::client::fake_lcu::{ActionUpdate, FakeLcu};
+    use crate::mock_lcu::{MockLcu, MockState};
+
+    fn pick_session(is_pick_turn: bool, teammate_intent: ChampionId) -> ChampSelectSession {
+        serde_json::from_value(serde_json::json!({
+            "actions": [[
+                {"actorCellId": 0, "championId": 0, "completed": false, "id": 1, "isAllyAction": true, "isInProgress": is_pick_turn, "pickTurn": 1, "type": "pick"}
+            ]],
+            "bans": {"myTeamBans": [], "numBans": 0, "theirTeamBans": []},
+            "gameId": 7,
+            "localPlayerCellId": 0,
+            "myTeam": [
+                {"assignedPosition": "middle", "cellId": 0},
+                {"assignedPosition": "top", "cellId": 1, "championPickIntent": teammate_intent}
+            ],
+            "timer": {"adjustedTimeLeftInPhase": 30000, "internalNowInEpochMs": 0, "isInfinite": false, "phase": "BAN_PICK", "totalTimeInPhase": 30000}
+        })).unwrap()
+    }
+
+    fn preferences() -> PickPreferences {
+        PickPreferences::from([(Position::Middle, vec![AHRI_ID]), (Position::Any, vec![84, AHRI_ID])])
+    }
+
+    fn pick_update(champion_id: ChampionId, mode: ActionMode) -> ActionUpdate {
+        ActionUpdate { champion_id, action_id: 1, mode }
+    }
+
+    #[tokio::test]
+    async fn hovers_the_preferred_pick_before_the_turn() {
+        let fake = FakeLcu::with_sessions([pick_session(false, 0)]);
+        fake.state().pickable_champions = vec![AHRI_ID, 84];
+        let outcome = pick_in_session(&fake, &pick_session(false, 0), &preferences()).await.unwrap();
+        assert_eq!(outcome, PickOutcome { champion_id: AHRI_ID, mode: ActionMode::Hover });
+        assert_eq!(fake.state().action_updates, [pick_update(AHRI_ID, ActionMode::Hover)]);
+    }
+
+    #[tokio::test]
+    async fn hover_is_not_repeated() {
+        let mut session = pick_session(false, 0);
+        session.actions[0][0].champion_id = AHRI_ID as i64;
+        let fake = FakeLcu::with_sessions([session.clone()]);
+        fake.state().pickable_champions = vec![AHRI_ID];
+        let outcome = pick_in_session(&fake, &session, &preferences()).await.unwrap();
+        assert_eq!(outcome.mode, ActionMode::Hover);
+        assert!(fake.state().action_updates.is_empty());
+    }
+
+    #[tokio::test]
+    async fn locks_the_fallback_on_the_turn() {
+        let fake = FakeLcu::with_sessions([pick_session(true, 0)]);
+        // not owned
+        fake.state().pickable_champions = vec![84];
+        let outcome = pick_in_session(&fake, &pick_session(true, 0), &preferences()).await.unwrap();
+        assert_eq!(outcome, PickOutcome { champion_id: 84, mode: ActionMode::LockIn });
+        assert_eq!(fake.state().action_updates, [pick_update(84, ActionMode::LockIn)]);
+    }
+
+    #[tokio::test]
+    async fn fails_without_a_pickable_champion() {
+        let fake = FakeLcu::with_sessions([pick_session(true, 0)]);
+        let result = pick_in_session(&fake, &pick_session(true, 0), &preferences()).await;
+        assert_eq!(result, Err(AppError::NoChampionToPick(vec![(AHRI_ID, SkipReason::NotPickable), (84, SkipReason::NotPickable)])));
+        assert!(fake.state().action_updates.is_empty());
+    }
+
+    #[tokio::test]
+    async fn auto_pick_loads_pickable_champions_once_per_game() {
+        let mock = MockLcu::start(0, MockState {
+            phase: GameflowPhase::ChampSelect,
+            pickable_champions: vec![AHRI_ID, 84],
+            ..Default::default()
+        }).await.unwrap();
+        let client = LolClient::from_lockfile_with(&mock.lockfile(), true).unwrap();
+        let mut socket = LcuWebSocket::connect(&client).await.unwrap();
+        let preferences = PickPreferences::from([(Position::Middle, vec![AHRI_ID])]);
+
+        let serve_sessions = async {
+            mock.wait_for_subscription(CHAMP_SELECT_SESSION_EVENT).await;
+            for session in [pick_session(false, 0), pick_session(false, 84), pick_session(true, 84)] {
+                mock.state().session = Some(session.clone());
+                mock.publish(CHAMP_SELECT_SESSION_ENDPOINT, "Update", serde_json::json!(session));
+            }
+        };
+        let (picked, ()) = tokio::time::timeout(Duration::from_secs(10), async {
+            tokio::join!(auto_pick(&client, &mut socket, &preferences), serve_sessions)
+        }).await.unwrap();
+        assert_eq!(picked.unwrap(), AHRI_ID);
+
+        let state = mock.state();
+        let pickable_requests = state.requests
+            .iter()
+            .filter(|request| request.path == PICKABLE_CHAMPIONS_ENDPOINT)
+            .count();
+        assert_eq!(pickable_requests, 1);
+        let last_update = state.requests.iter().rev().find(|request| request.method == "PATCH").unwrap();
+        assert_eq!(last_update.body, serde_json::json!({"championId": AHRI_ID, "completed": true}));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,277 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::{AppError, AppResult};
+use crate::client::lockfile::Lockfile;
+
+/// The client UI process, started with the same credentials as the lockfile ones.
+pub const LEAGUE_CLIENT_UX_PROCESS: &str = "LeagueClientUx";
+pub const APP_PORT_ARG: &str = "--app-port=";
+pub const REMOTING_AUTH_TOKEN_ARG: &str = "--remoting-auth-token=";
+/// Scans are reused for this long, starting PowerShell on each retry of each thread would be too slow.
+pub const PROCESS_SCAN_CACHE_MILLIS: u64 = 2000;
+
+static LAST_SCAN: Mutex<Option<(Instant, AppResult<Lockfile>)>> = Mutex::new(None);
+
+/// Finds the client credentials in the command line of the running `LeagueClientUx` process,
+/// for setups where the lockfile is missing or stale.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessScanner {
+    /// Lists the processes with PowerShell, on Windows.
+    PowerShell,
+    /// Reads `/proc` on Linux, or any directory with the same `<pid>/cmdline` layout, whatever the OS.
+    Proc(PathBuf),
+}
+
+impl Default for ProcessScanner {
+    fn default() -> Self {
+        if cfg!(windows) {
+            Self::PowerShell
+        } else {
+            Self::Proc(PathBuf::from("/proc"))
+        }
+    }
+}
+
+
+impl ProcessScanner {
+    pub fn scan(&self) -> AppResult<Lockfile> {
+        match self {
+            Self::PowerShell => scan_windows(),
+            Self::Proc(proc_root) => scan_proc(proc_root),
+        }
+    }
+}
+
+
+/// [`ProcessScanner::scan`] of the default scanner, reusing a scan younger than [`PROCESS_SCAN_CACHE_MILLIS`].
+pub fn scan_process() -> AppResult<Lockfile> {
+    let mut last_scan = LAST_SCAN.lock().unwrap_or_else(|err| err.into_inner());
+    match &*last_scan {
+        Some((scanned_at, result)) if scanned_at.elapsed() < Duration::from_millis(PROCESS_SCAN_CACHE_MILLIS) => result.clone(),
+        _ => {
+            let result = ProcessScanner::default().scan();
+            *last_scan = Some((Instant::now(), result.clone()));
+            result
+        }
+    }
+}
+
+
+/// Whether the executable of `args` is `LeagueClientUx`, given as a Windows or Unix path.
+pub fn is_league_client_ux(args: &[String]) -> bool {
+    args.first()
+        .and_then(|executable| executable.rsplit(['/', '\\']).next())
+        .is_some_and(|executable| executable.trim_end_matches(".exe") == LEAGUE_CLIENT_UX_PROCESS)
+}
+
+/// Same credentials as the lockfile written by the client, from the `LeagueClientUx` arguments.
+pub fn lockfile_from_args(pid: u32, args: &[String]) -> AppResult<Lockfile> {
+    let arg_value = |prefix: &str| args.iter().find_map(|arg| arg.strip_prefix(prefix)).unwrap_or_default().to_string();
+    let port = arg_value(APP_PORT_ARG);
+    let port = match port.parse() {
+        Ok(port) if port != 0 => port,
+        _ => return Err(AppError::LockfileInvalidPort(port)),
+    };
+    let password = arg_value(REMOTING_AUTH_TOKEN_ARG);
+    if password.is_empty() {
+        return Err(AppError::LockfileInvalidPassword(password));
+    }
+    Ok(Lockfile {
+        process_name: LEAGUE_CLIENT_UX_PROCESS.to_string(),
+        pid,
+        port,
+        password,
+        protocol: "https".to_string(),
+    })
+}
+
+/// Splits a Windows command line on spaces outside of double quotes, quotes removed.
+pub fn split_command_line(command_line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut arg = String::new();
+    let mut is_quoted = false;
+    for c in command_line.chars() {
+        match c {
+            '"' => is_quoted = !is_quoted,
+            ' ' | '\t' if !is_quoted => {
+                if !arg.is_empty() {
+                    args.push(std::mem::take(&mut arg));
+                }
+            }
+            c => arg.push(c),
+        }
+    }
+    if !arg.is_empty() {
+        args.push(arg);
+    }
+    args
+}
+
+
+/// Reads the null separated arguments of `<proc_root>/<pid>/cmdline`, Wine processes included.
+fn scan_proc(proc_root: &Path) -> AppResult<Lockfile> {
+    let entries = std::fs::read_dir(proc_root)?;
+    let mut error = None;
+    for entry in entries.filter_map(Result::ok) {
+        let Some(pid) = entry.file_name().to_str().and_then(|pid| pid.parse::<u32>().ok()) else {
+            continue;
+        };
+        // processes can exit while scanning, or belong to another user
+        let Ok(cmdline) = std::fs::read(entry.path().join("cmdline")) else {
+            continue;
+        };
+        let args = String::from_utf8_lossy(&cmdline)
+            .split('\0')
+            .filter(|arg| !arg.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        if !is_league_client_ux(&args) {
+            continue;
+        }
+        match lockfile_from_args(pid, &args) {
+            Ok(lockfile) => return Ok(lockfile),
+            Err(err) => error = Some(err),
+        }
+    }
+    Err(error.unwrap_or_else(|| AppError::ClientNotRunning(format!("{} process not found", LEAGUE_CLIENT_UX_PROCESS))))
+}
+
+
+/// Lists `LeagueClientUx.exe` processes as `<pid> <command line>` lines with PowerShell.
+fn scan_windows() -> AppResult<Lockfile> {
+    let script = format!(
+        "Get-CimInstance Win32_Process -Filter \"name = '{}.exe'\" | ForEach-Object {{ \"$($_.ProcessId) $($_.CommandLine)\" }}",
+        LEAGUE_CLIENT_UX_PROCESS,
+    );
+    let mut command = std::process::Command::new("powershell");
+    command.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW, don't flash a console over the GUI
+        command.creation_flags(0x08000000);
+    }
+    let output = command.output()?;
+    let mut error = None;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Some((pid, command_line)) = line.trim().split_once(' ') else {
+            continue;
+        };
+        let Ok(pid) = pid.parse() else {
+            continue;
+        };
+        match lockfile_from_args(pid, &split_command_line(command_line)) {
+            Ok(lockfile) => return Ok(lockfile),
+            Err(err) => error = Some(err),
+        }
+    }
+    Err(error.unwrap_or_else(|| AppError::ClientNotRunning(format!("{} process not found", LEAGUE_CLIENT_UX_PROCESS))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    /// A fake `/proc` entry, `args` joined by NUL like the kernel does.
+    fn write_process(proc_root: &TempDir, pid: &str, args: &[&str]) {
+        let mut cmdline = args.join("\0");
+        cmdline.push('\0');
+        proc_root.write(format!("{}/cmdline", pid), cmdline);
+    }
+
+    #[test]
+    fn scans_nul_separated_arguments() {
+        let proc_root = TempDir::new();
+        write_process(&proc_root, "1", &["/sbin/init"]);
+        write_process(&proc_root, "4242", &[
+            "/opt/riot/LeagueClientUx.exe",
+            "--riotclient-auth-token=other",
+            "--app-port=51234",
+            "--remoting-auth-token=s3cr3t",
+            "--region=EUW",
+        ]);
+        let lockfile = ProcessScanner::Proc(proc_root.path().into()).scan().unwrap();
+        assert_eq!(lockfile, Lockfile {
+            process_name: LEAGUE_CLIENT_UX_PROCESS.to_string(),
+            pid: 4242,
+            port: 51234,
+            password: "s3cr3t".to_string(),
+            protocol: "https".to_string(),
+        });
+    }
+
+    #[test]
+    fn scans_wine_processes() {
+        let proc_root = TempDir::new();
+        write_process(&proc_root, "977", &[
+            "C:\\Riot Games\\League of Legends\\LeagueClientUx.exe",
+            "--app-port=2999",
+            "--remoting-auth-token=wine",
+        ]);
+        let lockfile = ProcessScanner::Proc(proc_root.path().into()).scan().unwrap();
+        assert_eq!((lockfile.pid, lockfile.port, lockfile.password.as_str()), (977, 2999, "wine"));
+    }
+
+    #[test]
+    fn reports_missing_port() {
+        let proc_root = TempDir::new();
+        write_process(&proc_root, "12", &["LeagueClientUx.exe", "--remoting-auth-token=pw"]);
+        assert_eq!(ProcessScanner::Proc(proc_root.path().into()).scan(), Err(AppError::LockfileInvalidPort(String::new())));
+    }
+
+    #[test]
+    fn skips_non_numeric_entries() {
+        let proc_root = TempDir::new();
+        write_process(&proc_root, "self", &["LeagueClientUx.exe", "--app-port=1", "--remoting-auth-token=pw"]);
+        write_process(&proc_root, "12abc", &["LeagueClientUx.exe", "--app-port=1", "--remoting-auth-token=pw"]);
+        proc_root.write("version", "Linux");
+        // exited while scanning
+        std::fs::create_dir(proc_root.path().join("33")).unwrap();
+        let result = ProcessScanner::Proc(proc_root.path().into()).scan();
+        assert!(matches!(result, Err(AppError::ClientNotRunning(_))), "{:?}", result);
+    }
+
+    #[test]
+    fn recognizes_client_executable() {
+        assert!(is_league_client_ux(&args(&["LeagueClientUx"])));
+        assert!(is_league_client_ux(&args(&["D:/Games/League of Legends/LeagueClientUx.exe"])));
+        assert!(!is_league_client_ux(&args(&["LeagueClientUxRender.exe"])));
+        assert!(!is_league_client_ux(&args(&["LeagueClient.exe", "LeagueClientUx.exe"])));
+        assert!(!is_league_client_ux(&[]));
+    }
+
+    #[test]
+    fn reads_lockfile_from_args() {
+        let lockfile = lockfile_from_args(7, &args(&["LeagueClientUx.exe", "--remoting-auth-token=pw", "--app-port=443"])).unwrap();
+        assert_eq!((lockfile.pid, lockfile.port, lockfile.password.as_str()), (7, 443, "pw"));
+        assert_eq!(
+            lockfile_from_args(7, &args(&["--app-port=0", "--remoting-auth-token=pw"])),
+            Err(AppError::LockfileInvalidPort("0".to_string())),
+        );
+        assert_eq!(
+            lockfile_from_args(7, &args(&["--app-port=http", "--remoting-auth-token=pw"])),
+            Err(AppError::LockfileInvalidPort("http".to_string())),
+        );
+        assert_eq!(
+            lockfile_from_args(7, &args(&["--app-port=443"])),
+            Err(AppError::LockfileInvalidPassword(String::new())),
+        );
+    }
+
+    #[test]
+    fn splits_windows_command_lines() {
+        assert_eq!(
+            split_command_line(r#""C:\Riot Games\League of Legends\LeagueClientUx.exe"  "--app-port=2999" --remoting-auth-token=pw	--locale=en_US"#),
+            ["C:\\Riot Games\\League of Legends\\LeagueClientUx.exe", "--app-port=2999", "--remoting-auth-token=pw", "--locale=en_US"],
+        );
+        assert_eq!(split_command_line(r#"a "b c"d "" e"#), ["a", "b cd", "e"]);
+        assert!(split_command_line("   ").is_empty());
+    }
+}
\ No newline at end of file
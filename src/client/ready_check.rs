@@ -0,0 +1,211 @@
+use serde_derive::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::{AppError, AppResult, wait_n_millis};
+use crate::client::{BAN_AHRI_THREAD_RETRY_MILLIS, LolClient};
+use crate::client::api::LcuApi;
+use crate::client::websocket::LcuWebSocket;
+
+pub const READY_CHECK_ENDPOINT: &str = "/lol-matchmaking/v1/ready-check";
+pub const READY_CHECK_EVENT: &str = "OnJsonApiEvent_lol-matchmaking_v1_ready-check";
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReadyCheck {
+    #[serde(rename = "declinerIds")]
+    pub decliner_ids: Vec<i64>,
+    /// `None`, `Accepted` or `Declined`.
+    #[serde(rename = "playerResponse")]
+    pub player_response: String,
+    /// `Invalid`, `InProgress`, `EveryoneReady`, `StrangerNotReady`, `PartyNotReady` or `Error`.
+    pub state: String,
+    pub timer: f64,
+}
+
+impl ReadyCheck {
+    /// The queue popped and we didn't answer yet.
+    pub fn is_pending(&self) -> bool {
+        self.state == "InProgress" && self.player_response == "None"
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ReadyCheckOptions {
+    /// Wait before answering, so the queue pop doesn't look automated.
+    pub delay_millis: u64,
+    /// Decline instead of accepting while the chat status is `away`.
+    pub decline_when_away: bool,
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadyCheckResponse {
+    Accepted,
+    Declined,
+}
+
+
+impl LolClient {
+    pub async fn get_ready_check(&self) -> AppResult<ReadyCheck> {
+        self.get(READY_CHECK_ENDPOINT).await
+    }
+
+    pub async fn accept_ready_check(&self) -> AppResult<()> {
+        self.post(&format!("{}/accept", READY_CHECK_ENDPOINT), None).await
+    }
+
+    pub async fn decline_ready_check(&self) -> AppResult<()> {
+        self.post(&format!("{}/decline", READY_CHECK_ENDPOINT), None).await
+    }
+}
+
+
+/// Answers the current ready check if it's pending, `None` when there is nothing to answer.
+pub async fn answer_ready_check(client: &impl LcuApi, options: ReadyCheckOptions) -> AppResult<Option<ReadyCheckResponse>> {
+    if !client.get_ready_check().await?.is_pending() {
+        return Ok(None);
+    }
+    if options.delay_millis > 0 {
+        wait_n_millis(options.delay_millis).await;
+        // the ready check may have been answered or cancelled meanwhile
+        match client.get_ready_check().await {
+            Ok(ready_check) if ready_check.is_pending() => {}
+            Ok(_) | Err(AppError::NotFound(_)) => return Ok(None),
+            Err(err) => return Err(err),
+        }
+    }
+    // a chat service that is down shouldn't make us miss the game
+    let is_away = options.decline_when_away && client.is_away().await.unwrap_or_else(|err| {
+        println!("Failed to read the chat availability, accepting: {:?}", err);
+        false
+    });
+    if is_away {
+        client.decline_ready_check().await?;
+        Ok(Some(ReadyCheckResponse::Declined))
+    } else {
+        client.accept_ready_check().await?;
+        Ok(Some(ReadyCheckResponse::Accepted))
+    }
+}
+
+
+/// Answers every ready check until the websocket closes.
+pub async fn auto_accept(client: &impl LcuApi, socket: &mut LcuWebSocket, options: ReadyCheckOptions) -> AppResult<()> {
+    socket.subscribe(READY_CHECK_EVENT).await?;
+    // the queue may have popped before we subscribed
+    let mut is_pending = client.get_ready_check().await.is_ok_and(|ready_check| ready_check.is_pending());
+    loop {
+        if is_pending {
+            match answer_ready_check(client, options).await {
+                Ok(Some(response)) => println!("Ready check {:?}", response),
+                Ok(None) => {}
+                Err(err) => println!("Failed to answer ready check: {:?}", err),
+            }
+        }
+        let Some(event) = socket.next_event().await? else {
+            return Err(AppError::RiotClientError("Websocket closed".to_string()));
+        };
+        is_pending = event.event_type != "Delete"
+            && serde_json::from_value::<ReadyCheck>(event.data).is_ok_and(|ready_check| ready_check.is_pending());
+    }
+}
+
+
+/// Keeps answering ready checks of the client in `riot_path` until a stop is received on `receiver`,
+/// reconnecting whenever the client restarts.
+pub async fn ready_check_thread(riot_path: String, options: ReadyCheckOptions, mut receiver: mpsc::Receiver<bool>) -> AppResult<()> {
+    loop {
+        tokio::select! {
+            _ = receiver.recv() => return Ok(()),
+            result = async {
+                let client = LolClient::new(riot_path.clone()).await?;
+                let mut socket = LcuWebSocket::connect(&client).await?;
+                auto_accept(&client, &mut socket, options).await
+            } => if let Err(err) = result {
+                println!("Auto accept failed, retrying: {:?}", err);
+            },
+        }
+        tokio::select! {
+            _ = receiver.recv() => return Ok(()),
+            _ = wait_n_millis(BAN_AHRI_THREAD_RETRY_MILLIS) => {}
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::client::fake_lcu::{FakeLcu, FakeState};
+
+    fn pending_ready_check() -> ReadyCheck {
+        ReadyCheck {
+            player_response: "None".to_string(),
+            state: "InProgress".to_string(),
+            timer: 2.0,
+            ..Default::default()
+        }
+    }
+
+    fn fake_client(user_session: Option<serde_json::Value>) -> FakeLcu {
+        FakeLcu::new(FakeState {
+            ready_check: Some(pending_ready_check()),
+            user_session,
+            ..Default::default()
+        })
+    }
+
+    fn options(delay_millis: u64, decline_when_away: bool) -> ReadyCheckOptions {
+        ReadyCheckOptions { delay_millis, decline_when_away }
+    }
+
+    #[tokio::test]
+    async fn accepts_pending_ready_checks() {
+        let client = fake_client(Some(serde_json::json!({"availability": "away"})));
+        assert_eq!(answer_ready_check(&client, options(0, false)).await, Ok(Some(ReadyCheckResponse::Accepted)));
+        assert_eq!(client.state().ready_check_responses, [ReadyCheckResponse::Accepted]);
+        // already answered
+        assert_eq!(answer_ready_check(&client, options(0, false)).await, Ok(None));
+        assert_eq!(client.state().ready_check_responses.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn declines_when_away() {
+        let client = fake_client(Some(serde_json::json!({"availability": "away"})));
+        assert_eq!(answer_ready_check(&client, options(0, true)).await, Ok(Some(ReadyCheckResponse::Declined)));
+
+        let client = fake_client(Some(serde_json::json!({"availability": "chat"})));
+        assert_eq!(answer_ready_check(&client, options(0, true)).await, Ok(Some(ReadyCheckResponse::Accepted)));
+    }
+
+    #[tokio::test]
+    async fn accepts_when_the_chat_is_down() {
+        let client = fake_client(None);
+        assert_eq!(answer_ready_check(&client, options(0, true)).await, Ok(Some(ReadyCheckResponse::Accepted)));
+        assert_eq!(client.state().ready_check_responses, [ReadyCheckResponse::Accepted]);
+    }
+
+    #[tokio::test]
+    async fn skips_ready_checks_answered_or_cancelled_during_the_delay() {
+        for answer in [
+            // accepted manually
+            Some(ReadyCheck { player_response: "Accepted".to_string(), ..pending_ready_check() }),
+            // someone declined, the queue restarts
+            None,
+        ] {
+            let client = fake_client(None);
+            let (response, ()) = tokio::join!(
+                answer_ready_check(&client, options(300, false)),
+                async {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    client.state().ready_check = answer;
+                },
+            );
+            assert_eq!(response, Ok(None));
+            assert!(client.state().ready_check_responses.is_empty());
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,196 @@
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::client::champ_select_session::Timer;
+
+/// Never lock a late ban closer than this to the end of the phase.
+pub const MIN_LEAD_MILLIS: u64 = 1500;
+/// Clock offset samples further apart than this make the estimate unreliable.
+pub const MAX_OFFSET_SPREAD_MILLIS: i64 = 750;
+pub const MAX_OFFSET_SAMPLES: usize = 10;
+/// A single sample has no spread, so it can't tell whether the estimate is reliable.
+/// Until this many samples agree, the lock is moved [`MAX_OFFSET_SPREAD_MILLIS`] earlier.
+pub const MIN_OFFSET_SAMPLES: usize = 2;
+
+/// When to lock the ban once the ban turn started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BanTiming {
+    #[default]
+    Immediate,
+    /// Lock `lead_millis` before the end of the phase, hiding the ban until the last moment.
+    Late { lead_millis: u64 },
+}
+
+
+pub fn local_now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+
+/// Estimates the offset between the local clock and the client clock from the session timers.
+/// The offset doesn't depend on the phase, so the planning samples still count once the bans start.
+#[derive(Debug, Clone, Default)]
+pub struct ClockEstimator {
+    offsets: VecDeque<i64>,
+}
+
+
+impl ClockEstimator {
+    /// Records a timer received at `local_now_millis`.
+    pub fn add_sample(&mut self, timer: &Timer, local_now_millis: i64) {
+        if timer.internal_now_in_epoch_ms <= 0 {
+            return;
+        }
+        if self.offsets.len() == MAX_OFFSET_SAMPLES {
+            self.offsets.pop_front();
+        }
+        self.offsets.push_back(local_now_millis - timer.internal_now_in_epoch_ms);
+    }
+
+    /// Median of `local - client` clock offsets, `None` without samples.
+    pub fn offset(&self) -> Option<i64> {
+        let mut offsets = self.offsets.iter().copied().collect::<Vec<_>>();
+        offsets.sort_unstable();
+        offsets.get(offsets.len() / 2).copied()
+    }
+
+    /// Enough samples to check them against each other.
+    pub fn is_established(&self) -> bool {
+        self.offsets.len() >= MIN_OFFSET_SAMPLES
+    }
+
+    /// No samples disagreeing with each other, e.g. because the client froze.
+    pub fn is_consistent(&self) -> bool {
+        let min = self.offsets.iter().min();
+        let max = self.offsets.iter().max();
+        matches!((min, max), (Some(min), Some(max)) if max - min <= MAX_OFFSET_SPREAD_MILLIS)
+    }
+
+    /// Enough samples agreeing with each other.
+    pub fn is_reliable(&self) -> bool {
+        self.is_established() && self.is_consistent()
+    }
+
+    /// Local epoch millis at which the phase of `timer` ends, `None` once the samples disagree.
+    pub fn phase_end(&self, timer: &Timer) -> Option<i64> {
+        if timer.is_infinite || !self.is_consistent() {
+            return None;
+        }
+        Some(timer.internal_now_in_epoch_ms + timer.adjusted_time_left_in_phase + self.offset()?)
+    }
+
+    /// How long to wait before locking the ban, `None` when it should be locked right away:
+    /// immediate timing, unreliable estimate or already inside the safety margin.
+    /// An estimate that isn't established yet only locks earlier, more samples usually arrive meanwhile.
+    pub fn ban_delay(&self, timer: &Timer, timing: BanTiming, local_now_millis: i64) -> Option<Duration> {
+        let BanTiming::Late { lead_millis } = timing else {
+            return None;
+        };
+        let mut lead_millis = lead_millis.max(MIN_LEAD_MILLIS) as i64;
+        if !self.is_established() {
+            lead_millis += MAX_OFFSET_SPREAD_MILLIS;
+        }
+        let lock_at = self.phase_end(timer)? - lead_millis;
+        let delay = lock_at - local_now_millis;
+        (delay > 0).then(|| Duration::from_millis(delay as u64))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timer(phase: &str, client_now_millis: i64, time_left_millis: i64) -> Timer {
+        Timer {
+            adjusted_time_left_in_phase: time_left_millis,
+            internal_now_in_epoch_ms: client_now_millis,
+            is_infinite: false,
+            phase: phase.to_string(),
+            total_time_in_phase: 30_000,
+        }
+    }
+
+    /// Samples of a client clock `offset` millis behind the local one.
+    fn estimator(phase: &str, offsets: &[i64]) -> ClockEstimator {
+        let mut clock = ClockEstimator::default();
+        for (i, offset) in offsets.iter().enumerate() {
+            let client_now_millis = 1_000_000 + i as i64 * 1000;
+            clock.add_sample(&timer(phase, client_now_millis, 30_000), client_now_millis + offset);
+        }
+        clock
+    }
+
+    #[test]
+    fn offset_is_the_median() {
+        assert_eq!(ClockEstimator::default().offset(), None);
+        assert_eq!(estimator("BAN_PICK", &[100, 500, 120]).offset(), Some(120));
+        assert_eq!(estimator("BAN_PICK", &[-40]).offset(), Some(-40));
+    }
+
+    #[test]
+    fn samples_survive_phase_changes() {
+        let mut clock = estimator("PLANNING", &[100]);
+        assert!(!clock.is_established());
+        clock.add_sample(&timer("BAN_PICK", 2_000_000, 30_000), 2_000_120);
+        assert_eq!(clock.offset(), Some(120));
+        assert!(clock.is_reliable());
+    }
+
+    #[test]
+    fn samples_are_bounded() {
+        let offsets = (0..MAX_OFFSET_SAMPLES as i64 + 5).collect::<Vec<_>>();
+        let clock = estimator("BAN_PICK", &offsets);
+        assert_eq!(clock.offsets.len(), MAX_OFFSET_SAMPLES);
+        assert_eq!(clock.offset(), Some(10));
+    }
+
+    #[test]
+    fn late_ban_waits_for_the_lead() {
+        let clock = estimator("BAN_PICK", &[200, 210, 220]);
+        // the phase ends at local time 1_032_210, with the median offset
+        let timer = timer("BAN_PICK", 1_002_000, 30_000);
+        let timing = BanTiming::Late { lead_millis: 5000 };
+        assert_eq!(clock.ban_delay(&timer, timing, 1_002_210), Some(Duration::from_millis(25_000)));
+        assert_eq!(clock.ban_delay(&timer, BanTiming::Immediate, 1_002_210), None);
+        // inside the lead already
+        assert_eq!(clock.ban_delay(&timer, timing, 1_028_000), None);
+    }
+
+    #[test]
+    fn lead_is_clamped_to_the_minimum() {
+        let clock = estimator("BAN_PICK", &[0, 0]);
+        let timer = timer("BAN_PICK", 1_001_000, 10_000);
+        let delay = clock.ban_delay(&timer, BanTiming::Late { lead_millis: 0 }, 1_001_000);
+        assert_eq!(delay, Some(Duration::from_millis(10_000 - MIN_LEAD_MILLIS)));
+    }
+
+    #[test]
+    fn single_sample_locks_earlier() {
+        let timing = BanTiming::Late { lead_millis: 5000 };
+        let timer = timer("BAN_PICK", 1_000_000, 30_000);
+        // a single sample has no spread
+        let clock = estimator("BAN_PICK", &[0]);
+        assert!(!clock.is_reliable());
+        let delay = 30_000 - 5000 - MAX_OFFSET_SPREAD_MILLIS as u64;
+        assert_eq!(clock.ban_delay(&timer, timing, 1_000_000), Some(Duration::from_millis(delay)));
+        // too close to the end to wait for another sample
+        assert_eq!(clock.ban_delay(&timer, timing, 1_000_000 + delay as i64), None);
+    }
+
+    #[test]
+    fn unreliable_estimate_bans_right_away() {
+        let timing = BanTiming::Late { lead_millis: 5000 };
+        let timer = timer("BAN_PICK", 1_000_000, 30_000);
+        // samples disagreeing, e.g. the client froze
+        let clock = estimator("BAN_PICK", &[0, MAX_OFFSET_SPREAD_MILLIS + 1]);
+        assert!(!clock.is_reliable());
+        assert_eq!(clock.ban_delay(&timer, timing, 1_000_000), None);
+        // infinite timers have no end
+        let clock = estimator("BAN_PICK", &[0, 0]);
+        assert_eq!(clock.ban_delay(&Timer { is_infinite: true, ..timer }, timing, 1_000_000), None);
+    }
+}
\ No newline at end of file
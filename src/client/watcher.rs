@@ -0,0 +1,209 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::client::lockfile::Lockfile;
+use crate::client::lockfile_path;
+use crate::client::process::ProcessScanner;
+use crate::wait_n_millis;
+
+pub const LOCKFILE_POLL_MILLIS: u64 = 500;
+/// Process scans start PowerShell on Windows, they are spaced out from this interval...
+pub const PROCESS_SCAN_MIN_INTERVAL_MILLIS: u64 = 2000;
+/// ...doubling up to this one while no client is found.
+pub const PROCESS_SCAN_MAX_INTERVAL_MILLIS: u64 = 30_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatcherEvent {
+    /// The client started, or restarted with new credentials.
+    Connected(Lockfile),
+    /// The lockfile was deleted or nothing listens on its port anymore, the client stopped.
+    Disconnected,
+}
+
+
+/// Watches `<riot_path>/League of Legends/lockfile` for creation, change and deletion,
+/// falling back to the `LeagueClientUx` process arguments while there is no lockfile or nothing listens on its port.
+#[derive(Debug, Clone)]
+pub struct LockfileWatcher {
+    lockfile_path: PathBuf,
+    lockfile: Option<Lockfile>,
+    scanner: ProcessScanner,
+    next_process_scan: Instant,
+    process_scan_interval: Duration,
+}
+
+
+impl LockfileWatcher {
+    pub fn new(riot_path: String) -> Self {
+        Self::with_scanner(riot_path, ProcessScanner::default())
+    }
+
+    pub fn with_scanner(riot_path: String, scanner: ProcessScanner) -> Self {
+        Self {
+            lockfile_path: lockfile_path(riot_path),
+            lockfile: None,
+            scanner,
+            next_process_scan: Instant::now(),
+            process_scan_interval: Duration::from_millis(PROCESS_SCAN_MIN_INTERVAL_MILLIS),
+        }
+    }
+
+    pub fn lockfile(&self) -> Option<&Lockfile> {
+        self.lockfile.as_ref()
+    }
+
+    /// Waits until the lockfile is created, changed or deleted.
+    /// Polls on a blocking thread, the port probe and the process scan block.
+    pub async fn next_event(&mut self) -> WatcherEvent {
+        loop {
+            let mut watcher = self.clone();
+            let polled = tokio::task::spawn_blocking(move || {
+                let event = watcher.poll();
+                (watcher, event)
+            }).await;
+            if let Ok((watcher, event)) = polled {
+                *self = watcher;
+                if let Some(event) = event {
+                    return event;
+                }
+            }
+            wait_n_millis(LOCKFILE_POLL_MILLIS).await;
+        }
+    }
+
+    /// Checks the lockfile once, returns an event if it differs from the last seen one. Blocks.
+    pub fn poll(&mut self) -> Option<WatcherEvent> {
+        let lockfile = if self.lockfile_path.exists() {
+            // a lockfile which doesn't parse is still being written, wait for the next poll
+            Some(Lockfile::read(&self.lockfile_path).ok()?)
+        } else {
+            None
+        };
+        let lockfile = match lockfile {
+            Some(lockfile) if lockfile.is_listening() => Some(lockfile),
+            // no lockfile, or one left by a crashed client: some setups write the lockfile elsewhere,
+            // the process arguments hold the same credentials
+            _ => self.scan_process(),
+        };
+        if lockfile == self.lockfile {
+            return None;
+        }
+        self.lockfile.clone_from(&lockfile);
+        Some(match lockfile {
+            Some(lockfile) => WatcherEvent::Connected(lockfile),
+            None => WatcherEvent::Disconnected,
+        })
+    }
+
+    /// Scans the processes, less and less often while no client is found.
+    /// Between scans, the client found last is kept while its port listens.
+    fn scan_process(&mut self) -> Option<Lockfile> {
+        let now = Instant::now();
+        if now < self.next_process_scan {
+            return self.lockfile.clone().filter(Lockfile::is_listening);
+        }
+        let lockfile = self.scanner.scan().ok();
+        self.process_scan_interval = match lockfile {
+            Some(_) => Duration::from_millis(PROCESS_SCAN_MIN_INTERVAL_MILLIS),
+            None => (self.process_scan_interval * 2).min(Duration::from_millis(PROCESS_SCAN_MAX_INTERVAL_MILLIS)),
+        };
+        self.next_process_scan = now + self.process_scan_interval;
+        lockfile
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn write_lockfile(riot_dir: &TempDir, port: u16) {
+        riot_dir.write("League of Legends/lockfile", format!("LeagueClient:1:{}:pw:https", port));
+    }
+
+    fn write_client_process(proc_root: &TempDir, port: u16) {
+        proc_root.write("4242/cmdline", format!("LeagueClientUx.exe\0--app-port={}\0--remoting-auth-token=pw\0", port));
+    }
+
+    /// Scanning `proc_root` instead of the processes of the machine.
+    fn watcher(riot_dir: &TempDir, proc_root: &TempDir) -> LockfileWatcher {
+        LockfileWatcher::with_scanner(riot_dir.path().to_string_lossy().to_string(), ProcessScanner::Proc(proc_root.path().into()))
+    }
+
+    #[test]
+    fn stale_lockfile_is_not_connected() {
+        let (riot_dir, proc_root) = (TempDir::new(), TempDir::new());
+        // bound then dropped, nothing listens on it anymore
+        let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        write_lockfile(&riot_dir, port);
+        let mut watcher = watcher(&riot_dir, &proc_root);
+        assert_eq!(watcher.poll(), None);
+        assert_eq!(watcher.lockfile(), None);
+    }
+
+    #[test]
+    fn connects_while_listening() {
+        let (riot_dir, proc_root) = (TempDir::new(), TempDir::new());
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        write_lockfile(&riot_dir, port);
+        let mut watcher = watcher(&riot_dir, &proc_root);
+        assert!(matches!(watcher.poll(), Some(WatcherEvent::Connected(lockfile)) if lockfile.port == port));
+        assert_eq!(watcher.poll(), None);
+
+        // crashed, the lockfile stays behind
+        drop(listener);
+        assert_eq!(watcher.poll(), Some(WatcherEvent::Disconnected));
+    }
+
+    #[test]
+    fn falls_back_to_the_process_arguments() {
+        let (riot_dir, proc_root) = (TempDir::new(), TempDir::new());
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        write_client_process(&proc_root, port);
+        let mut watcher = watcher(&riot_dir, &proc_root);
+        assert!(matches!(watcher.poll(), Some(WatcherEvent::Connected(lockfile)) if lockfile.port == port && lockfile.pid == 4242));
+        // kept between scans while listening
+        assert_eq!(watcher.poll(), None);
+
+        drop(listener);
+        assert_eq!(watcher.poll(), Some(WatcherEvent::Disconnected));
+    }
+
+    #[test]
+    fn process_scans_back_off_while_not_found() {
+        let (riot_dir, proc_root) = (TempDir::new(), TempDir::new());
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut watcher = watcher(&riot_dir, &proc_root);
+        assert_eq!(watcher.poll(), None);
+        assert_eq!(watcher.process_scan_interval, Duration::from_millis(2 * PROCESS_SCAN_MIN_INTERVAL_MILLIS));
+        for _ in 0..10 {
+            watcher.next_process_scan = Instant::now();
+            watcher.poll();
+        }
+        assert_eq!(watcher.process_scan_interval, Duration::from_millis(PROCESS_SCAN_MAX_INTERVAL_MILLIS));
+
+        // not scanned again before the interval elapsed
+        write_client_process(&proc_root, listener.local_addr().unwrap().port());
+        assert_eq!(watcher.poll(), None);
+        watcher.next_process_scan = Instant::now();
+        assert!(matches!(watcher.poll(), Some(WatcherEvent::Connected(_))));
+        assert_eq!(watcher.process_scan_interval, Duration::from_millis(PROCESS_SCAN_MIN_INTERVAL_MILLIS));
+    }
+
+    #[tokio::test]
+    async fn next_event_polls_off_the_runtime() {
+        let (riot_dir, proc_root) = (TempDir::new(), TempDir::new());
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        write_lockfile(&riot_dir, port);
+        let mut watcher = watcher(&riot_dir, &proc_root);
+        assert!(matches!(watcher.next_event().await, WatcherEvent::Connected(lockfile) if lockfile.port == port));
+        assert_eq!(watcher.lockfile().map(|lockfile| lockfile.port), Some(port));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,311 @@
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use futures_util::{SinkExt, StreamExt};
+use serde_derive::{Deserialize, Serialize};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{Connector, MaybeTlsStream, WebSocketStream};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::{AppError, AppResult, ChampionId};
+use crate::client::{accept_invalid_certs, BanOutcome, execute_ban, LolClient, prepare_ban, tls_connector};
+use crate::client::api::LcuApi;
+use crate::client::champ_select_session::ChampSelectSession;
+use crate::client::schedule::{BanTiming, ClockEstimator, local_now_millis};
+
+pub const CHAMP_SELECT_SESSION_EVENT: &str = "OnJsonApiEvent_lol-champ-select_v1_session";
+
+/// WAMP opcodes used by the LCU websocket.
+pub const WAMP_SUBSCRIBE: u8 = 5;
+pub const WAMP_UNSUBSCRIBE: u8 = 6;
+pub const WAMP_EVENT: u8 = 8;
+/// Topic of every event, [`event_topic`] gives the one of a single endpoint.
+pub const ALL_EVENTS: &str = "OnJsonApiEvent";
+
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LcuEvent {
+    pub data: serde_json::Value,
+    #[serde(rename = "eventType")]
+    pub event_type: String,
+    pub uri: String,
+}
+
+
+pub struct LcuWebSocket {
+    stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+}
+
+
+impl LcuWebSocket {
+    /// Connects to the websocket of the client described by `lol_client`.
+    pub async fn connect(lol_client: &LolClient) -> AppResult<Self> {
+        Self::connect_url_with(&format!("wss://127.0.0.1:{}", lol_client.port), &lol_client.password, lol_client.accept_invalid_certs).await
+    }
+
+    /// Connects to any LCU-like websocket, `ws://` urls are accepted for local testing.
+    pub async fn connect_url(url: &str, password: &str) -> AppResult<Self> {
+        Self::connect_url_with(url, password, accept_invalid_certs()).await
+    }
+
+    async fn connect_url_with(url: &str, password: &str, accept_invalid_certs: bool) -> AppResult<Self> {
+        let mut request = url
+            .into_client_request()
+            .map_err(|err| AppError::RiotClientError(err.to_string()))?;
+        let auth = BASE64_STANDARD.encode(format!("riot:{}", password).as_str());
+        request.headers_mut().insert("Authorization", format!("Basic {}", auth).parse().unwrap());
+        let (stream, _) = tokio_tungstenite::connect_async_tls_with_config(
+            request,
+            None,
+            false,
+            Some(Connector::NativeTls(tls_connector(accept_invalid_certs)?)),
+        )
+            .await
+            .map_err(|err| match err {
+                tungstenite::Error::Tls(err) => AppError::CertificateRejected(err.to_string()),
+                tungstenite::Error::Io(err) => AppError::ClientNotRunning(err.to_string()),
+                err => AppError::RiotClientError(err.to_string()),
+            })?;
+        Ok(Self { stream })
+    }
+
+    pub async fn subscribe(&mut self, event: &str) -> AppResult<()> {
+        self.stream
+            .send(Message::Text(serde_json::json!([WAMP_SUBSCRIBE, event]).to_string()))
+            .await
+            .map_err(|err| AppError::RiotClientError(err.to_string()))
+    }
+
+    /// Waits for the next event of a subscribed topic, `None` once the connection is closed.
+    pub async fn next_event(&mut self) -> AppResult<Option<LcuEvent>> {
+        while let Some(message) = self.stream.next().await {
+            let message = message.map_err(|err| AppError::RiotClientError(err.to_string()))?;
+            let Message::Text(text) = message else {
+                continue;
+            };
+            if let Some(event) = parse_event(&text) {
+                return Ok(Some(event));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Waits for the next champ select session update, `None` once the connection is closed.
+    pub async fn next_champ_select_session(&mut self) -> AppResult<Option<ChampSelectSession>> {
+        while let Some(event) = self.next_event().await? {
+            if event.event_type == "Delete" {
+                continue;
+            }
+            if let Ok(session) = serde_json::from_value::<ChampSelectSession>(event.data) {
+                return Ok(Some(session));
+            }
+        }
+        Ok(None)
+    }
+}
+
+
+/// Parses a WAMP event frame: `[8, "<topic>", {"data": ..., "eventType": ..., "uri": ...}]`.
+pub fn parse_event(text: &str) -> Option<LcuEvent> {
+    let (opcode, _topic, event) = serde_json::from_str::<(u8, String, LcuEvent)>(text).ok()?;
+    (opcode == WAMP_EVENT).then_some(event)
+}
+
+
+/// Topic of the events of `uri`, e.g. `OnJsonApiEvent_lol-champ-select_v1_session` for `/lol-champ-select/v1/session`.
+pub fn event_topic(uri: &str) -> String {
+    format!("{}{}", ALL_EVENTS, uri.replace('/', "_"))
+}
+
+
+/// Returns true when the local player has a ban action in progress.
+pub fn is_ban_turn(session: &ChampSelectSession) -> bool {
+    session.current_ban_action().is_some()
+}
+
+
+/// Listens to champ select updates, hovers the ban during the planning phase
+/// and bans once the local player's ban turn starts, right away or late depending on `timing`.
+/// The bannable champions are fetched once per game and the ban is resolved from each update,
+/// so locking it is a single request.
+pub async fn auto_ban(lol_client: &impl LcuApi, socket: &mut LcuWebSocket, champion_ids: &[ChampionId], timing: BanTiming) -> AppResult<BanOutcome> {
+    socket.subscribe(CHAMP_SELECT_SESSION_EVENT).await?;
+    let mut hovered = None;
+    let mut clock = ClockEstimator::default();
+    let mut bannable_champions: Option<(i64, Vec<ChampionId>)> = None;
+    let mut next_session = socket.next_champ_select_session().await?;
+    while let Some(session) = next_session {
+        clock.add_sample(&session.timer, local_now_millis());
+        if session.next_action("ban").is_none() {
+            next_session = socket.next_champ_select_session().await?;
+            continue;
+        }
+        if bannable_champions.as_ref().map(|(game_id, _)| *game_id) != Some(session.game_id) {
+            match lol_client.get_bannable_champion_ids().await {
+                Ok(champion_ids) => bannable_champions = Some((session.game_id, champion_ids)),
+                Err(err) => {
+                    println!("Failed to load bannable champions: {:?}", err);
+                    next_session = socket.next_champ_select_session().await?;
+                    continue;
+                }
+            }
+        }
+        let bannable = bannable_champions.as_ref().map(|(_, champion_ids)| champion_ids.as_slice()).unwrap_or_default();
+        let prepared_ban = prepare_ban(&session, champion_ids, bannable);
+        if is_ban_turn(&session) {
+            let prepared_ban = prepared_ban?;
+            let Some(delay) = clock.ban_delay(&session.timer, timing, local_now_millis()) else {
+                return execute_ban(lol_client, prepared_ban).await;
+            };
+            // any update restarts the wait with a fresh timer, or bans right away if the estimate got unreliable
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {
+                    return execute_ban(lol_client, prepared_ban).await;
+                }
+                update = socket.next_champ_select_session() => {
+                    next_session = update?;
+                    continue;
+                }
+            }
+        }
+        let is_hover_needed = session.timer.phase == "PLANNING"
+            && session.next_action("ban").is_some_and(|action| hovered != Some((session.game_id, action.id)));
+        if is_hover_needed {
+            match prepared_ban {
+                Ok(prepared_ban) => match execute_ban(lol_client, prepared_ban).await {
+                    Ok(_) => hovered = session.next_action("ban").map(|action| (session.game_id, action.id)),
+                    Err(err) => println!("Failed to hover ban: {:?}", err),
+                },
+                Err(err) => println!("Failed to hover ban: {:?}", err),
+            }
+        }
+        next_session = socket.next_champ_select_session().await?;
+    }
+    Err(AppError::RiotClientError("Websocket closed".to_string()))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::AHRI_ID;
+    use crate::client::{ActionMode, BANNABLE_CHAMPIONS_ENDPOINT};
+    use crate::client::gameflow::GameflowPhase;
+    use crate::mock_lcu::{MockLcu, MockState};
+
+    /// Frames recorded from a ranked champ select: the planning phase, then the local player's ban turn.
+    const BAN_TURN_EVENTS: &str = include_str!("../../tests/fixtures/ban_turn_events.jsonl");
+
+    /// Replays recorded frames through the mock once `topic` is subscribed, its state following the events.
+    async fn serve_frames(mock: &MockLcu, topic: &str, frames: &str) {
+        mock.wait_for_subscription(topic).await;
+        for frame in frames.lines() {
+            let event = parse_event(frame).expect("recorded event frame");
+            {
+                let mut state = mock.state();
+                match event.uri.as_str() {
+                    "/lol-gameflow/v1/gameflow-phase" => state.phase = serde_json::from_value(event.data.clone()).unwrap(),
+                    "/lol-champ-select/v1/session" => state.session = serde_json::from_value(event.data.clone()).ok(),
+                    _ => {}
+                }
+            }
+            mock.publish(&event.uri, &event.event_type, event.data);
+        }
+    }
+
+    #[test]
+    fn parses_recorded_frames() {
+        let events = BAN_TURN_EVENTS.lines().map(parse_event).collect::<Option<Vec<_>>>().unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(event_topic(&events[1].uri), CHAMP_SELECT_SESSION_EVENT);
+        let session = serde_json::from_value::<ChampSelectSession>(events[2].data.clone()).unwrap();
+        assert!(is_ban_turn(&session));
+        assert_eq!(parse_event(r#"[5, "OnJsonApiEvent"]"#), None);
+    }
+
+    #[tokio::test]
+    async fn auto_ban_locks_in_once_on_the_ban_turn() {
+        let mock = MockLcu::start(0, MockState {
+            phase: GameflowPhase::Lobby,
+            bannable_champions: vec![AHRI_ID],
+            ..Default::default()
+        }).await.unwrap();
+        let client = LolClient::from_lockfile_with(&mock.lockfile(), true).unwrap();
+        let mut socket = LcuWebSocket::connect(&client).await.unwrap();
+
+        let (outcome, ()) = tokio::time::timeout(Duration::from_secs(10), async {
+            tokio::join!(
+                auto_ban(&client, &mut socket, &[AHRI_ID], BanTiming::Immediate),
+                serve_frames(&mock, CHAMP_SELECT_SESSION_EVENT, BAN_TURN_EVENTS),
+            )
+        }).await.unwrap();
+        let outcome = outcome.unwrap();
+        assert_eq!((outcome.champion_id, outcome.mode), (AHRI_ID, ActionMode::LockIn));
+
+        let state = mock.state();
+        let action_updates = state.requests
+            .iter()
+            .filter(|request| request.method == "PATCH")
+            .map(|request| (request.path.as_str(), request.body["completed"] == true))
+            .collect::<Vec<_>>();
+        // hovered during the planning phase, locked once the turn starts
+        assert_eq!(action_updates, [
+            ("/lol-champ-select/v1/session/actions/1", false),
+            ("/lol-champ-select/v1/session/actions/1", true),
+        ]);
+        // loaded once for the game, the ban turn only costs the PATCH
+        let bannable_requests = state.requests.iter().filter(|request| request.path == BANNABLE_CHAMPIONS_ENDPOINT).count();
+        assert_eq!(bannable_requests, 1);
+    }
+
+    #[tokio::test]
+    async fn late_auto_ban_locks_in_near_the_deadline() {
+        let mock = MockLcu::start(0, MockState {
+            phase: GameflowPhase::Lobby,
+            bannable_champions: vec![AHRI_ID],
+            ..Default::default()
+        }).await.unwrap();
+        let client = LolClient::from_lockfile_with(&mock.lockfile(), true).unwrap();
+        let mut socket = LcuWebSocket::connect(&client).await.unwrap();
+
+        // the recorded frames, timed as if the ban phase had 4 seconds left right now
+        let start = Instant::now();
+        let now_millis = local_now_millis();
+        let frames = BAN_TURN_EVENTS
+            .lines()
+            .map(|frame| {
+                let mut frame = serde_json::from_str::<serde_json::Value>(frame).unwrap();
+                if let Some(timer) = frame[2]["data"].get_mut("timer") {
+                    timer["internalNowInEpochMs"] = now_millis.into();
+                    if timer["phase"] == "BAN_PICK" {
+                        timer["adjustedTimeLeftInPhase"] = 4000.into();
+                    }
+                }
+                frame.to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let timing = BanTiming::Late { lead_millis: 1500 };
+
+        let (outcome, ()) = tokio::time::timeout(Duration::from_secs(10), async {
+            tokio::join!(
+                auto_ban(&client, &mut socket, &[AHRI_ID], timing),
+                serve_frames(&mock, CHAMP_SELECT_SESSION_EVENT, &frames),
+            )
+        }).await.unwrap();
+        assert_eq!(outcome.unwrap().mode, ActionMode::LockIn);
+
+        let state = mock.state();
+        let lock = state.requests
+            .iter()
+            .find(|request| request.method == "PATCH" && request.body["completed"] == true)
+            .expect("locked ban");
+        // locked 1.5 seconds before the end of the phase, not on the first update of the ban turn
+        let locked_after = lock.received_at - start;
+        assert!(locked_after >= Duration::from_millis(2300), "locked after {:?}", locked_after);
+        assert!(locked_after <= Duration::from_millis(3500), "locked after {:?}", locked_after);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,380 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use serde_derive::{Deserialize, Serialize};
+use toml::Table;
+
+use crate::{AHRI_ID, AppError, AppResult, ChampionId};
+use crate::client::champion_catalog::ChampionCatalog;
+use crate::client::DEFAULT_RIOT_PATH;
+use crate::client::pick::PickPreferences;
+use crate::client::ready_check::ReadyCheckOptions;
+use crate::client::schedule::BanTiming;
+
+pub const CONFIG_DIR_NAME: &str = "ban_ahri_client";
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+/// Flat JSON config of previous releases, written in the working directory.
+pub const LEGACY_CONFIG_FILE_NAME: &str = "ban_ahri_client.json";
+pub const CONFIG_VERSION: i64 = 1;
+/// `MIGRATIONS[n]` upgrades a version `n` config to version `n + 1`.
+const MIGRATIONS: [fn(&mut Table); CONFIG_VERSION as usize] = [migrate_v0];
+
+/// Saves are numbered when requested, so a write finishing late never overwrites a newer config.
+static NEXT_SAVE_ID: AtomicU64 = AtomicU64::new(0);
+/// Id of the last save written to each path, locked while writing so writes don't interleave.
+static LAST_WRITTEN_SAVE_IDS: Mutex<BTreeMap<PathBuf, u64>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub version: i64,
+    pub riot_path: String,
+    /// Trust any certificate instead of only the Riot root one, for unusual setups.
+    pub accept_invalid_certs: bool,
+    /// Name of an iced theme, e.g. `Dark` or `Tokyo Night`.
+    pub theme: String,
+    pub ban: BanConfig,
+    pub ready_check: ReadyCheckConfig,
+    pub pick: PickConfig,
+    pub window: WindowConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            riot_path: DEFAULT_RIOT_PATH.to_string(),
+            accept_invalid_certs: false,
+            theme: "Dark".to_string(),
+            ban: BanConfig::default(),
+            ready_check: ReadyCheckConfig::default(),
+            pick: PickConfig::default(),
+            window: WindowConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BanConfig {
+    /// Champions to ban, highest priority first.
+    pub champions: Vec<ChampionId>,
+    /// Arm the auto ban at startup.
+    pub auto_ban: bool,
+    /// Lock auto bans at the last moment instead of as soon as the turn starts.
+    pub late: bool,
+    /// How long before the end of the ban phase a late ban is locked.
+    pub late_lead_millis: u64,
+}
+
+impl Default for BanConfig {
+    fn default() -> Self {
+        Self {
+            champions: vec![AHRI_ID],
+            auto_ban: false,
+            late: false,
+            late_lead_millis: 3000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReadyCheckConfig {
+    /// Accept ready checks automatically.
+    pub auto_accept: bool,
+    pub delay_millis: u64,
+    /// Decline ready checks instead while the chat status is away.
+    pub decline_when_away: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PickConfig {
+    /// Hover and lock picks automatically.
+    pub auto_pick: bool,
+    pub preferences: PickPreferences,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowConfig {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            width: 440.0,
+            height: 380.0,
+        }
+    }
+}
+
+
+impl Config {
+    /// `config.toml` in the platform config directory, e.g. `%APPDATA%\ban_ahri_client` or `~/.config/ban_ahri_client`.
+    pub fn path() -> PathBuf {
+        dirs::config_dir()
+            .map(|config_dir| config_dir.join(CONFIG_DIR_NAME))
+            .unwrap_or_default()
+            .join(CONFIG_FILE_NAME)
+    }
+
+    /// Loads the config from disk, falling back to the default one if it is missing or invalid.
+    pub fn load() -> Self {
+        Self::read().unwrap_or_else(|err| {
+            println!("Failed to load config, using the default one: {:?}", err);
+            Self::default()
+        })
+    }
+
+    /// Reads and migrates the config, the legacy JSON one is converted and written as TOML the first time.
+    pub fn read() -> AppResult<Self> {
+        let path = Self::path();
+        if path.exists() {
+            let table = std::fs::read_to_string(path)?
+                .parse::<Table>()
+                .map_err(|err| AppError::InvalidConfig(err.to_string()))?;
+            return Self::from_table(table);
+        }
+        if !Path::new(LEGACY_CONFIG_FILE_NAME).exists() {
+            return Ok(Self::default());
+        }
+        let table = serde_json::from_str::<Table>(&std::fs::read_to_string(LEGACY_CONFIG_FILE_NAME)?)
+            .map_err(|err| AppError::InvalidConfig(err.to_string()))?;
+        let config = Self::from_table(table)?;
+        config.write()?;
+        println!("Migrated {} to {}", LEGACY_CONFIG_FILE_NAME, Self::path().display());
+        Ok(config)
+    }
+
+    /// Upgrades `table` to [`CONFIG_VERSION`], a config without version being a legacy one.
+    /// Configs written by a newer version are read as is, unknown keys are ignored.
+    pub fn from_table(mut table: Table) -> AppResult<Self> {
+        let version = table.get("version").and_then(toml::Value::as_integer).unwrap_or(0);
+        if version > CONFIG_VERSION {
+            println!("Config version {} is newer than {}, some options may be ignored", version, CONFIG_VERSION);
+        }
+        for migration in MIGRATIONS.iter().skip(version.max(0) as usize) {
+            migration(&mut table);
+        }
+        table.insert("version".to_string(), version.max(CONFIG_VERSION).into());
+        table.try_into().map_err(|err: toml::de::Error| AppError::InvalidConfig(err.to_string()))
+    }
+
+    pub fn ban_timing(&self) -> BanTiming {
+        if self.ban.late {
+            BanTiming::Late { lead_millis: self.ban.late_lead_millis }
+        } else {
+            BanTiming::Immediate
+        }
+    }
+
+    pub fn ready_check_options(&self) -> ReadyCheckOptions {
+        ReadyCheckOptions {
+            delay_millis: self.ready_check.delay_millis,
+            decline_when_away: self.ready_check.decline_when_away,
+        }
+    }
+
+    pub fn write(&self) -> AppResult<()> {
+        self.write_to(&Self::path(), NEXT_SAVE_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Writes to a temporary file first, so a crash never leaves a truncated config.
+    /// Skipped when a save requested after `save_id` was already written.
+    fn write_to(&self, path: &Path, save_id: u64) -> AppResult<()> {
+        let mut last_written_save_ids = LAST_WRITTEN_SAVE_IDS.lock().unwrap_or_else(|err| err.into_inner());
+        if last_written_save_ids.get(path).is_some_and(|&last_written_save_id| last_written_save_id > save_id) {
+            return Ok(());
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let content = toml::to_string_pretty(self)
+            .map_err(|err| AppError::InvalidConfig(err.to_string()))?;
+        let temp_path = path.with_extension(format!("toml.{}.{}.tmp", std::process::id(), save_id));
+        std::fs::write(&temp_path, content)?;
+        std::fs::rename(temp_path, path)?;
+        last_written_save_ids.insert(path.to_path_buf(), save_id);
+        Ok(())
+    }
+
+    /// Writes the config off the async runtime, the save is ordered from the call, not from when the future runs.
+    pub fn save(self) -> impl Future<Output = AppResult<()>> {
+        let save_id = NEXT_SAVE_ID.fetch_add(1, Ordering::Relaxed);
+        async move {
+            tokio::task::spawn_blocking(move || self.write_to(&Self::path(), save_id))
+                .await
+                .map_err(|err| AppError::IoError(err.to_string()))?
+        }
+    }
+}
+
+
+/// Version 0 is the flat JSON config, options are grouped by feature since version 1.
+fn migrate_v0(table: &mut Table) {
+    let mut ban = Table::new();
+    move_key(table, "champions", &mut ban, "champions");
+    move_key(table, "late_ban", &mut ban, "late");
+    move_key(table, "late_ban_lead_millis", &mut ban, "late_lead_millis");
+    let mut ready_check = Table::new();
+    move_key(table, "auto_accept", &mut ready_check, "auto_accept");
+    move_key(table, "ready_check_delay_millis", &mut ready_check, "delay_millis");
+    move_key(table, "decline_when_away", &mut ready_check, "decline_when_away");
+    let mut pick = Table::new();
+    move_key(table, "auto_pick", &mut pick, "auto_pick");
+    move_key(table, "pick_preferences", &mut pick, "preferences");
+    table.insert("ban".to_string(), ban.into());
+    table.insert("ready_check".to_string(), ready_check.into());
+    table.insert("pick".to_string(), pick.into());
+}
+
+fn move_key(from: &mut Table, from_key: &str, to: &mut Table, to_key: &str) {
+    if let Some(value) = from.remove(from_key) {
+        to.insert(to_key.to_string(), value);
+    }
+}
+
+
+/// Parses a comma separated list of champion ids or names, e.g. `"103, wukong"`.
+pub fn parse_champion_list(input: &str, catalog: &ChampionCatalog) -> Option<Vec<ChampionId>> {
+    let champions = input
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| catalog.resolve(part))
+        .collect::<Option<Vec<_>>>()?;
+    if champions.is_empty() {
+        None
+    } else {
+        Some(champions)
+    }
+}
+
+/// Like [`parse_champion_list`] but an empty input is an empty list.
+pub fn parse_optional_champion_list(input: &str, catalog: &ChampionCatalog) -> Option<Vec<ChampionId>> {
+    if input.trim().is_empty() {
+        Some(Vec::new())
+    } else {
+        parse_champion_list(input, catalog)
+    }
+}
+
+pub fn format_champion_list(champions: &[ChampionId], catalog: &ChampionCatalog) -> String {
+    champions
+        .iter()
+        .map(|&champion_id| catalog.name(champion_id))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::pick::Position;
+    use crate::test_support::TempDir;
+
+    fn config_with_path(riot_path: &str) -> Config {
+        Config {
+            riot_path: riot_path.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn read_riot_path(path: &Path) -> String {
+        let table = std::fs::read_to_string(path).unwrap().parse::<Table>().unwrap();
+        Config::from_table(table).unwrap().riot_path
+    }
+
+    #[test]
+    fn concurrent_writes_keep_the_newest_config() {
+        let dir = TempDir::new();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        let save_ids = (0..16).map(|_| NEXT_SAVE_ID.fetch_add(1, Ordering::Relaxed)).collect::<Vec<_>>();
+        let newest = format!("riot {}", save_ids.last().unwrap());
+        // written in reverse order of request, on threads racing each other
+        std::thread::scope(|scope| {
+            for &save_id in save_ids.iter().rev() {
+                let path = &path;
+                scope.spawn(move || config_with_path(&format!("riot {}", save_id)).write_to(path, save_id).unwrap());
+            }
+        });
+        assert_eq!(read_riot_path(&path), newest);
+        // no temporary file left behind
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn older_save_is_skipped() {
+        let dir = TempDir::new();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        let older_save_id = NEXT_SAVE_ID.fetch_add(1, Ordering::Relaxed);
+        let newer_save_id = NEXT_SAVE_ID.fetch_add(1, Ordering::Relaxed);
+        config_with_path("newer").write_to(&path, newer_save_id).unwrap();
+        config_with_path("older").write_to(&path, older_save_id).unwrap();
+        assert_eq!(read_riot_path(&path), "newer");
+    }
+
+    #[test]
+    fn migrates_legacy_json_config() {
+        let legacy = r#"{
+            "riot_path": "D:/Riot Games",
+            "champions": [103, 84],
+            "late_ban": true,
+            "late_ban_lead_millis": 2000,
+            "auto_accept": true,
+            "ready_check_delay_millis": 1500,
+            "decline_when_away": true,
+            "auto_pick": true,
+            "pick_preferences": {"middle": [103], "any": [1]},
+            "theme": "Light"
+        }"#;
+        let config = Config::from_table(serde_json::from_str::<Table>(legacy).unwrap()).unwrap();
+        assert_eq!(config, Config {
+            version: CONFIG_VERSION,
+            riot_path: "D:/Riot Games".to_string(),
+            theme: "Light".to_string(),
+            ban: BanConfig {
+                champions: vec![103, 84],
+                late: true,
+                late_lead_millis: 2000,
+                ..Default::default()
+            },
+            ready_check: ReadyCheckConfig {
+                auto_accept: true,
+                delay_millis: 1500,
+                decline_when_away: true,
+            },
+            pick: PickConfig {
+                auto_pick: true,
+                preferences: PickPreferences::from([(Position::Middle, vec![103]), (Position::Any, vec![1])]),
+            },
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn reads_newer_configs() {
+        let newer = format!(r#"
+            version = {}
+            riot_path = "/games/riot"
+            future_option = true
+
+            [ban]
+            champions = [84]
+            late_ban_jitter_millis = 200
+        "#, CONFIG_VERSION + 1);
+        let config = Config::from_table(newer.parse::<Table>().unwrap()).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION + 1);
+        assert_eq!(config.riot_path, "/games/riot");
+        // not migrated again as a legacy config
+        assert_eq!(config.ban.champions, [84]);
+    }
+}
\ No newline at end of file
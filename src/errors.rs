@@ -1,12 +1,256 @@
+use serde_derive::Deserialize;
 use thiserror::Error;
 
-#[derive(Error, Debug)]
+use crate::ChampionId;
+use crate::client::champ_select_session::SkipReason;
+use crate::client::gameflow::GameflowPhase;
+
+/// Error body returned by the LCU for non 2xx responses.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+pub struct LcuErrorResponse {
+    #[serde(rename = "errorCode", default)]
+    pub error_code: String,
+    #[serde(rename = "httpStatus", default)]
+    pub http_status: u16,
+    #[serde(default)]
+    pub message: String,
+}
+
+impl std::fmt::Display for LcuErrorResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}: {}", self.http_status, self.error_code, self.message)
+    }
+}
+
+
+#[derive(Error, Debug, Clone, PartialEq)]
 pub enum AppError {
     #[error("IO error occurred: {0}")]
-    IoError(#[from] std::io::Error),
+    IoError(String),
     #[error("Request error occurred: {0}")]
-    ReqwestError(#[from] reqwest::Error),
+    ReqwestError(String),
     #[error("Riot client error: {0}")]
     RiotClientError(String),
+    #[error("League of Legends not found in {0}")]
+    RiotPathNotFound(String),
+    #[error("League client is not running: {0}")]
+    ClientNotRunning(String),
+    #[error("League client certificate rejected: {0}")]
+    CertificateRejected(String),
+    #[error("Lockfile is empty")]
+    LockfileEmpty,
+    #[error("Invalid lockfile process name: {0:?}")]
+    LockfileInvalidProcessName(String),
+    #[error("Invalid lockfile pid: {0:?}")]
+    LockfileInvalidPid(String),
+    #[error("Invalid lockfile port: {0:?}")]
+    LockfileInvalidPort(String),
+    #[error("Invalid lockfile password: {0:?}")]
+    LockfileInvalidPassword(String),
+    #[error("Invalid lockfile protocol: {0:?}")]
+    LockfileInvalidProtocol(String),
+    #[error("Unexpected lockfile fields: {0:?}")]
+    LockfileExtraFields(String),
+    #[error("Bad request: {0}")]
+    BadRequest(LcuErrorResponse),
+    #[error("Unauthorized: {0}")]
+    Unauthorized(LcuErrorResponse),
+    #[error("Not found: {0}")]
+    NotFound(LcuErrorResponse),
+    #[error("Client RPC error: {0}")]
+    RpcError(LcuErrorResponse),
+    #[error("Client internal error: {0}")]
+    ServerError(LcuErrorResponse),
+    #[error("Unexpected client response: {0}")]
+    UnexpectedResponse(LcuErrorResponse),
+    #[error("Invalid client response: {0}")]
+    InvalidResponse(String),
+    #[error("Not in champion select, the client is in {0}")]
+    NotInChampSelect(GameflowPhase),
+    #[error("No champion left to ban: {}", format_skipped(.0))]
+    NoChampionToBan(Vec<(ChampionId, SkipReason)>),
+    #[error("No champion left to pick: {}", format_skipped(.0))]
+    NoChampionToPick(Vec<(ChampionId, SkipReason)>),
+    #[error("The client didn't confirm the ban of champion {0}")]
+    BanNotConfirmed(ChampionId),
+    /// The unknown name and the closest champion names.
+    #[error("Unknown champion: {0:?}{}", format_suggestions(.1))]
+    UnknownChampion(String, Vec<String>),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("Invalid config: {0}")]
+    InvalidConfig(String),
+}
+
+
+fn format_skipped(skipped: &[(ChampionId, SkipReason)]) -> String {
+    skipped
+        .iter()
+        .map(|(champion_id, reason)| format!("{} {}", champion_id, reason))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn format_suggestions(suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        return String::new();
+    }
+    format!(", did you mean {}?", suggestions.join(", "))
+}
+
+impl AppError {
+    /// Maps a non 2xx LCU response to its variant, using the parsed error body when there is one.
+    pub fn from_lcu_response(status: u16, body: &str) -> Self {
+        let response = serde_json::from_str::<LcuErrorResponse>(body)
+            .ok()
+            .map(|response| LcuErrorResponse {
+                http_status: if response.http_status == 0 { status } else { response.http_status },
+                ..response
+            })
+            .unwrap_or_else(|| LcuErrorResponse {
+                http_status: status,
+                message: body.to_string(),
+                ..Default::default()
+            });
+        match (response.error_code.as_str(), status) {
+            // a missing champ select session is an `RPC_ERROR` with a 404 status
+            (_, 404) => AppError::NotFound(response),
+            ("RPC_ERROR", _) => AppError::RpcError(response),
+            (_, 400) => AppError::BadRequest(response),
+            (_, 401) | (_, 403) => AppError::Unauthorized(response),
+            (_, 500..=599) => AppError::ServerError(response),
+            _ => AppError::UnexpectedResponse(response),
+        }
+    }
+
+    /// A hint shown to the user on how to fix the error, if there is one.
+    pub fn suggestion(&self) -> Option<&'static str> {
+        match self {
+            AppError::RiotPathNotFound(_) => Some("Check the Riot Games installation path."),
+            AppError::ClientNotRunning(_) => Some("Start the League client and log in."),
+            AppError::CertificateRejected(_) => Some("Another program may be listening on the client port, otherwise set accept_invalid_certs = true in the config."),
+            AppError::LockfileEmpty
+            | AppError::LockfileInvalidProcessName(_)
+            | AppError::LockfileInvalidPid(_)
+            | AppError::LockfileInvalidPort(_)
+            | AppError::LockfileInvalidPassword(_)
+            | AppError::LockfileInvalidProtocol(_)
+            | AppError::LockfileExtraFields(_) => Some("Restart the League client to rewrite its lockfile."),
+            AppError::Unauthorized(_) => Some("The client restarted, wait for it to reconnect."),
+            AppError::NotFound(_) | AppError::NotInChampSelect(_) => Some("Join a champion select first."),
+            AppError::RpcError(_) | AppError::ServerError(_) => Some("The client is busy, try again in a moment."),
+            AppError::NoChampionToBan(_) => Some("Add more champions to the ban list."),
+            AppError::NoChampionToPick(_) => Some("Add more champions to the pick list of your role."),
+            AppError::BanNotConfirmed(_) => Some("Check the ban in the client, it may have to be locked manually."),
+            AppError::UnknownChampion(..) => Some("Use the champion id, or start the client once so the champion list gets cached."),
+            AppError::InvalidConfig(_) => Some("Fix or delete the config file to start from the default one."),
+            _ => None,
+        }
+    }
+
+    /// Process exit code of the headless binary, stable so scripts can branch on it.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            AppError::IoError(_)
+            | AppError::ReqwestError(_)
+            | AppError::RiotClientError(_)
+            | AppError::InvalidResponse(_) => 1,
+            AppError::InvalidArgument(_) => 2,
+            AppError::RiotPathNotFound(_) => 3,
+            AppError::ClientNotRunning(_) => 4,
+            AppError::LockfileEmpty
+            | AppError::LockfileInvalidProcessName(_)
+            | AppError::LockfileInvalidPid(_)
+            | AppError::LockfileInvalidPort(_)
+            | AppError::LockfileInvalidPassword(_)
+            | AppError::LockfileInvalidProtocol(_)
+            | AppError::LockfileExtraFields(_) => 5,
+            AppError::Unauthorized(_) => 6,
+            AppError::NotFound(_) | AppError::NotInChampSelect(_) => 7,
+            AppError::BadRequest(_)
+            | AppError::RpcError(_)
+            | AppError::ServerError(_)
+            | AppError::UnexpectedResponse(_) => 8,
+            AppError::NoChampionToBan(_) | AppError::NoChampionToPick(_) => 9,
+            AppError::BanNotConfirmed(_) => 10,
+            AppError::UnknownChampion(..) => 11,
+            AppError::InvalidConfig(_) => 12,
+            AppError::CertificateRejected(_) => 13,
+        }
+    }
 }
-pub type AppResult<T> = Result<T, AppError>;
\ No newline at end of file
+
+impl From<std::io::Error> for AppError {
+    fn from(err: std::io::Error) -> Self {
+        AppError::IoError(err.to_string())
+    }
+}
+
+impl From<reqwest::Error> for AppError {
+    fn from(err: reqwest::Error) -> Self {
+        if is_certificate_error(&err) {
+            AppError::CertificateRejected(err.to_string())
+        } else if err.is_connect() {
+            AppError::ClientNotRunning(err.to_string())
+        } else {
+            AppError::ReqwestError(err.to_string())
+        }
+    }
+}
+
+/// Whether a TLS error caused `err`, e.g. a certificate not signed by the pinned root.
+fn is_certificate_error(err: &(dyn std::error::Error + 'static)) -> bool {
+    let mut source = Some(err);
+    while let Some(err) = source {
+        if err.is::<native_tls::Error>() {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(error_code: &str, http_status: u16, message: &str) -> LcuErrorResponse {
+        LcuErrorResponse {
+            error_code: error_code.to_string(),
+            http_status,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn maps_rpc_errors_by_status() {
+        let body = r#"{"errorCode":"RPC_ERROR","httpStatus":404,"implementationDetails":{},"message":"No active delegate"}"#;
+        assert_eq!(AppError::from_lcu_response(404, body), AppError::NotFound(response("RPC_ERROR", 404, "No active delegate")));
+        let body = r#"{"errorCode":"RPC_ERROR","httpStatus":500,"message":"Unable to process action change"}"#;
+        assert_eq!(AppError::from_lcu_response(500, body), AppError::RpcError(response("RPC_ERROR", 500, "Unable to process action change")));
+    }
+
+    #[test]
+    fn maps_unauthorized() {
+        let body = r#"{"errorCode":"UNAUTHORIZED","httpStatus":401,"message":"Invalid credentials"}"#;
+        assert_eq!(AppError::from_lcu_response(401, body), AppError::Unauthorized(response("UNAUTHORIZED", 401, "Invalid credentials")));
+    }
+
+    #[test]
+    fn keeps_non_json_bodies_as_message() {
+        assert_eq!(
+            AppError::from_lcu_response(502, "<html>Bad Gateway</html>"),
+            AppError::ServerError(response("", 502, "<html>Bad Gateway</html>")),
+        );
+    }
+
+    #[test]
+    fn missing_http_status_falls_back_to_the_response_status() {
+        let body = r#"{"errorCode":"","httpStatus":0,"message":"Invalid champion"}"#;
+        assert_eq!(AppError::from_lcu_response(400, body), AppError::BadRequest(response("", 400, "Invalid champion")));
+        assert_eq!(AppError::from_lcu_response(418, r#"{"message":"teapot"}"#), AppError::UnexpectedResponse(response("", 418, "teapot")));
+    }
+}
\ No newline at end of file
@@ -2,31 +2,19 @@ pub mod client;
 
 pub mod ui;
 pub mod errors;
+pub mod config;
+pub mod cli;
+#[cfg(any(test, feature = "mock-lcu"))]
+pub mod mock_lcu;
+#[cfg(test)]
+mod test_support;
 
-pub const AHRI_ID: i32 = 103;
+pub type ChampionId = i32;
 
+pub const AHRI_ID: ChampionId = 103;
 
-#[derive(Debug, Clone)]
-pub enum AppError {
-    IoError(String),
-    ReqwestError(String),
-    RiotClientError(String),
-}
 
-impl From<std::io::Error> for AppError {
-    fn from(err: std::io::Error) -> Self {
-        AppError::IoError(err.to_string())
-    }
-}
-
-impl From<reqwest::Error> for AppError {
-    fn from(err: reqwest::Error) -> Self {
-        AppError::ReqwestError(err.to_string())
-    }
-}
-
-
-pub type AppResult<T> = Result<T, AppError>;
+pub use errors::{AppError, AppResult};
 
 
 pub async fn wait_n_millis(n: u64) {
@@ -1,11 +1,13 @@
 use iced::{Application, Settings, Size};
 
+use ban_ahri_client::config::Config;
 use ban_ahri_client::ui::application::MainApp;
 
 fn main() -> iced::Result {
+    let window = Config::load().window;
     MainApp::run(Settings {
         window: iced::window::Settings {
-            size: Size::new(360.0, 175.0),
+            size: Size::new(window.width, window.height),
             ..iced::window::Settings::default()
         },
         ..Settings::default()
@@ -0,0 +1,504 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use futures_util::{SinkExt, StreamExt};
+use serde_derive::Deserialize;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio_native_tls::TlsStream;
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::protocol::Role;
+
+use crate::{AppError, AppResult, ChampionId, wait_n_millis};
+use crate::client::{BANNABLE_CHAMPIONS_ENDPOINT, CHAMP_SELECT_SESSION_ENDPOINT};
+use crate::client::champ_select_session::{ChampSelectSession, Timer};
+use crate::client::champion_catalog::{CHAMPION_SUMMARY_ENDPOINT, ChampionSummary};
+use crate::client::gameflow::{GAMEFLOW_PHASE_ENDPOINT, GameflowPhase};
+use crate::client::lockfile::Lockfile;
+use crate::client::pick::PICKABLE_CHAMPIONS_ENDPOINT;
+use crate::client::schedule::local_now_millis;
+use crate::client::websocket::{ALL_EVENTS, event_topic, LcuEvent, WAMP_EVENT, WAMP_SUBSCRIBE, WAMP_UNSUBSCRIBE};
+
+/// Self-signed certificate of the mock, for development only.
+/// Clients need [`crate::client::set_accept_invalid_certs`].
+pub const MOCK_CERTIFICATE: &[u8] = include_bytes!("../assets/mock_lcu/cert.pem");
+pub const MOCK_PRIVATE_KEY: &[u8] = include_bytes!("../assets/mock_lcu/key.pem");
+pub const MOCK_PASSWORD: &str = "mock-lcu-password";
+pub const ACTIONS_ENDPOINT: &str = "/lol-champ-select/v1/session/actions/";
+pub const CHAT_ME_ENDPOINT: &str = "/lol-chat/v1/me";
+/// A draft pick from the lobby to the game start, played when no scenario file is given.
+pub const DEFAULT_SCENARIO: &str = include_str!("../assets/mock_lcu/scenario.json");
+/// Events kept for slow websockets before they miss some.
+const EVENT_CAPACITY: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockRequest {
+    pub received_at: Instant,
+    pub method: String,
+    pub path: String,
+    /// JSON body, `Null` when there is none.
+    pub body: serde_json::Value,
+}
+
+
+/// What the mock serves, shared with the caller so it can be scripted while the server runs.
+#[derive(Debug, Clone, Default)]
+pub struct MockState {
+    pub phase: GameflowPhase,
+    pub session: Option<ChampSelectSession>,
+    pub bannable_champions: Vec<ChampionId>,
+    pub pickable_champions: Vec<ChampionId>,
+    pub champions: Vec<ChampionSummary>,
+    /// Served on [`CHAT_ME_ENDPOINT`], e.g. `{"availability": "away"}`.
+    pub user_session: serde_json::Value,
+    /// Every authorized request, oldest first.
+    pub requests: Vec<MockRequest>,
+    /// Topics subscribed by the connected websockets, once per socket.
+    pub subscriptions: Vec<String>,
+}
+
+
+/// A local HTTPS server answering like the LCU, with basic auth, keep-alive connections
+/// and the WAMP websocket publishing the gameflow phase and champ select session changes.
+#[derive(Debug, Clone)]
+pub struct MockLcu {
+    pub port: u16,
+    pub password: String,
+    state: Arc<Mutex<MockState>>,
+    events: broadcast::Sender<LcuEvent>,
+}
+
+
+impl MockLcu {
+    /// Serves `state` on `port`, `0` for any free one.
+    pub async fn start(port: u16, state: MockState) -> AppResult<Self> {
+        let identity = native_tls::Identity::from_pkcs8(MOCK_CERTIFICATE, MOCK_PRIVATE_KEY)
+            .map_err(|err| AppError::RiotClientError(err.to_string()))?;
+        let acceptor = native_tls::TlsAcceptor::new(identity)
+            .map_err(|err| AppError::RiotClientError(err.to_string()))?;
+        let acceptor = tokio_native_tls::TlsAcceptor::from(acceptor);
+        let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+        let mock = Self {
+            port: listener.local_addr()?.port(),
+            password: MOCK_PASSWORD.to_string(),
+            state: Arc::new(Mutex::new(state)),
+            events: broadcast::channel(EVENT_CAPACITY).0,
+        };
+        let server = mock.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let acceptor = acceptor.clone();
+                let server = server.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = server.serve_connection(&acceptor, stream).await {
+                        println!("Mock LCU connection failed: {:?}", err);
+                    }
+                });
+            }
+        });
+        Ok(mock)
+    }
+
+    /// Credentials of the mock, as the client would write them.
+    pub fn lockfile(&self) -> Lockfile {
+        Lockfile {
+            process_name: "LeagueClient".to_string(),
+            pid: std::process::id(),
+            port: self.port,
+            password: self.password.clone(),
+            protocol: "https".to_string(),
+        }
+    }
+
+    pub fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Sends an event of `uri` to the websockets subscribed to it.
+    pub fn publish(&self, uri: &str, event_type: &str, data: serde_json::Value) {
+        // no websocket connected yet is fine
+        let _ = self.events.send(LcuEvent {
+            data,
+            event_type: event_type.to_string(),
+            uri: uri.to_string(),
+        });
+    }
+
+    /// Waits until a websocket subscribed to `topic`, events published before are lost.
+    pub async fn wait_for_subscription(&self, topic: &str) {
+        while !self.state().subscriptions.iter().any(|subscribed| subscribed == topic) {
+            wait_n_millis(10).await;
+        }
+    }
+
+    /// Plays the steps of `scenario` in real time.
+    pub async fn play(&self, scenario: &Scenario) {
+        for step in &scenario.steps {
+            wait_n_millis(step.delay_millis).await;
+            self.apply_step(step, &scenario.session);
+        }
+    }
+
+    /// Applies `step` and publishes the resulting phase and session changes.
+    /// Entering champ select starts from `template`, leaving it ends the session.
+    pub fn apply_step(&self, step: &ScenarioStep, template: &ChampSelectSession) {
+        let mut state = self.state();
+        let previous_phase = state.phase;
+        let previous_session = state.session.clone();
+        if let Some(phase) = step.phase {
+            state.phase = phase;
+            state.session = match phase {
+                GameflowPhase::ChampSelect => state.session.take().or_else(|| Some(template.clone())),
+                _ => None,
+            };
+        }
+        if let Some(session) = state.session.as_mut() {
+            if let Some(timer_phase) = &step.timer_phase {
+                session.timer = Timer {
+                    adjusted_time_left_in_phase: step.time_left_millis,
+                    internal_now_in_epoch_ms: local_now_millis(),
+                    is_infinite: false,
+                    phase: timer_phase.clone(),
+                    total_time_in_phase: step.time_left_millis,
+                };
+            }
+            for update in &step.actions {
+                let Some(action) = session.actions.iter_mut().flatten().find(|action| action.id == update.id) else {
+                    continue;
+                };
+                action.champion_id = update.champion_id.unwrap_or(action.champion_id);
+                action.is_in_progress = update.is_in_progress.unwrap_or(action.is_in_progress);
+                action.completed = update.completed.unwrap_or(action.completed);
+            }
+        }
+        let (phase, session) = (state.phase, state.session.clone());
+        drop(state);
+
+        if phase != previous_phase {
+            self.publish(GAMEFLOW_PHASE_ENDPOINT, "Update", serde_json::json!(phase));
+        }
+        match (&previous_session, &session) {
+            (None, Some(session)) => self.publish(CHAMP_SELECT_SESSION_ENDPOINT, "Create", serde_json::json!(session)),
+            (Some(previous), Some(session)) if previous != session => {
+                self.publish(CHAMP_SELECT_SESSION_ENDPOINT, "Update", serde_json::json!(session))
+            }
+            (Some(_), None) => self.publish(CHAMP_SELECT_SESSION_ENDPOINT, "Delete", serde_json::Value::Null),
+            _ => {}
+        }
+        match &session {
+            Some(session) => println!("Mock LCU: {} {}", phase, session.timer.phase),
+            None => println!("Mock LCU: {}", phase),
+        }
+    }
+
+    async fn serve_connection(&self, acceptor: &tokio_native_tls::TlsAcceptor, stream: TcpStream) -> AppResult<()> {
+        stream.set_nodelay(true)?;
+        // port probes like `Lockfile::is_listening` close before the handshake
+        let Ok(stream) = acceptor.accept(stream).await else {
+            return Ok(());
+        };
+        let mut reader = BufReader::new(stream);
+        loop {
+            let mut request_line = String::new();
+            if reader.read_line(&mut request_line).await? == 0 {
+                return Ok(());
+            }
+            let mut parts = request_line.split_whitespace();
+            let method = parts.next().unwrap_or_default().to_string();
+            let path = parts.next().unwrap_or_default().to_string();
+            let mut content_length = 0;
+            let mut authorization = String::new();
+            let mut is_closing = false;
+            let mut websocket_key = None;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).await?;
+                let header = header.trim_end();
+                if header.is_empty() {
+                    break;
+                }
+                let Some((name, value)) = header.split_once(':') else {
+                    continue;
+                };
+                match name.trim().to_lowercase().as_str() {
+                    "content-length" => content_length = value.trim().parse().unwrap_or_default(),
+                    "authorization" => authorization = value.trim().to_string(),
+                    "connection" => is_closing = value.trim().eq_ignore_ascii_case("close"),
+                    "sec-websocket-key" => websocket_key = Some(value.trim().to_string()),
+                    _ => {}
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).await?;
+
+            let expected_authorization = format!("Basic {}", BASE64_STANDARD.encode(format!("riot:{}", self.password)));
+            let is_authorized = authorization == expected_authorization;
+            if let (Some(websocket_key), true) = (&websocket_key, is_authorized) {
+                let head = format!(
+                    "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+                    derive_accept_key(websocket_key.as_bytes()),
+                );
+                let mut stream = reader.into_inner();
+                stream.write_all(head.as_bytes()).await?;
+                stream.flush().await?;
+                return self.serve_websocket(WebSocketStream::from_raw_socket(stream, Role::Server, None).await).await;
+            }
+            let (status, response) = if is_authorized {
+                self.handle(&method, &path, &body)
+            } else {
+                (401, Some(error_body(401, "UNAUTHORIZED", "Invalid credentials")))
+            };
+            let response = response.map(|response| response.to_string()).unwrap_or_default();
+            let head = format!(
+                "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+                status,
+                reason_phrase(status),
+                response.len(),
+            );
+            let stream = reader.get_mut();
+            stream.write_all(head.as_bytes()).await?;
+            stream.write_all(response.as_bytes()).await?;
+            stream.flush().await?;
+            if is_closing || websocket_key.is_some() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Forwards the published events to the topics subscribed by the socket, until it closes.
+    async fn serve_websocket(&self, mut socket: WebSocketStream<TlsStream<TcpStream>>) -> AppResult<()> {
+        let mut topics = Vec::new();
+        let result = self.forward_events(&mut socket, &mut topics).await;
+        self.remove_subscriptions(&topics);
+        result
+    }
+
+    /// Removes one subscription per topic, the same topic can be subscribed by other sockets.
+    fn remove_subscriptions(&self, topics: &[String]) {
+        let mut state = self.state();
+        for topic in topics {
+            if let Some(index) = state.subscriptions.iter().position(|subscribed| subscribed == topic) {
+                state.subscriptions.remove(index);
+            }
+        }
+    }
+
+    async fn forward_events(&self, socket: &mut WebSocketStream<TlsStream<TcpStream>>, topics: &mut Vec<String>) -> AppResult<()> {
+        let mut events = self.events.subscribe();
+        loop {
+            tokio::select! {
+                message = socket.next() => {
+                    let Some(Ok(message)) = message else {
+                        return Ok(());
+                    };
+                    match message {
+                        Message::Text(text) => match serde_json::from_str::<(u8, String)>(&text) {
+                            Ok((WAMP_SUBSCRIBE, topic)) if !topics.contains(&topic) => {
+                                self.state().subscriptions.push(topic.clone());
+                                topics.push(topic);
+                            }
+                            Ok((WAMP_UNSUBSCRIBE, topic)) if topics.contains(&topic) => {
+                                self.remove_subscriptions(std::slice::from_ref(&topic));
+                                topics.retain(|subscribed| *subscribed != topic);
+                            }
+                            _ => {}
+                        },
+                        Message::Close(_) => return Ok(()),
+                        _ => {}
+                    }
+                }
+                event = events.recv() => {
+                    let event = match event {
+                        Ok(event) => event,
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => return Ok(()),
+                    };
+                    let topic = event_topic(&event.uri);
+                    for subscribed in topics.iter().filter(|subscribed| **subscribed == topic || *subscribed == ALL_EVENTS) {
+                        socket.send(Message::Text(serde_json::json!([WAMP_EVENT, subscribed, event]).to_string()))
+                            .await
+                            .map_err(|err| AppError::RiotClientError(err.to_string()))?;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Answers a request from the current state, `None` for an empty 204 body.
+    fn handle(&self, method: &str, path: &str, body: &[u8]) -> (u16, Option<serde_json::Value>) {
+        let mut state = self.state();
+        state.requests.push(MockRequest {
+            received_at: Instant::now(),
+            method: method.to_string(),
+            path: path.to_string(),
+            body: serde_json::from_slice(body).unwrap_or_default(),
+        });
+        match (method, path) {
+            ("GET", GAMEFLOW_PHASE_ENDPOINT) => (200, Some(serde_json::json!(state.phase))),
+            ("GET", CHAMP_SELECT_SESSION_ENDPOINT) => match &state.session {
+                Some(session) => (200, Some(serde_json::json!(session))),
+                None => (404, Some(error_body(404, "RPC_ERROR", "No active delegate"))),
+            },
+            ("GET", BANNABLE_CHAMPIONS_ENDPOINT) => (200, Some(serde_json::json!(state.bannable_champions))),
+            ("GET", PICKABLE_CHAMPIONS_ENDPOINT) => (200, Some(serde_json::json!(state.pickable_champions))),
+            ("GET", CHAMPION_SUMMARY_ENDPOINT) => (200, Some(serde_json::json!(state.champions))),
+            ("GET", CHAT_ME_ENDPOINT) => (200, Some(state.user_session.clone())),
+            ("PATCH", path) if path.starts_with(ACTIONS_ENDPOINT) => {
+                let action_id = path[ACTIONS_ENDPOINT.len()..].parse::<i64>().ok();
+                let update = serde_json::from_slice::<serde_json::Value>(body).unwrap_or_default();
+                let action = state.session
+                    .as_mut()
+                    .zip(action_id)
+                    .and_then(|(session, action_id)| session.actions.iter_mut().flatten().find(|action| action.id == action_id));
+                match action {
+                    Some(action) if !action.completed => {
+                        if let Some(champion_id) = update["championId"].as_i64() {
+                            action.champion_id = champion_id;
+                        }
+                        if update["completed"].as_bool() == Some(true) {
+                            action.completed = true;
+                            action.is_in_progress = false;
+                        }
+                        self.publish(CHAMP_SELECT_SESSION_ENDPOINT, "Update", serde_json::json!(state.session));
+                        (204, None)
+                    }
+                    Some(_) => (500, Some(error_body(500, "RPC_ERROR", "Action already completed"))),
+                    None => (404, Some(error_body(404, "RPC_ERROR", "No such action"))),
+                }
+            }
+            _ => (404, Some(error_body(404, "RESOURCE_NOT_FOUND", &format!("Invalid URI format: {}", path)))),
+        }
+    }
+}
+
+
+/// A scripted client: the champions it knows, the champ select it starts and the steps played on it.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Scenario {
+    /// Served as the champion summary, and as the bannable and pickable champions.
+    pub champions: Vec<ChampionSummary>,
+    pub user_session: serde_json::Value,
+    /// Session as served by the client, started with every action pending when a step enters champ select.
+    pub session: ChampSelectSession,
+    pub steps: Vec<ScenarioStep>,
+}
+
+impl Scenario {
+    /// Reads a JSON scenario, see `assets/mock_lcu/scenario.json`.
+    pub fn read(path: impl AsRef<Path>) -> AppResult<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn parse(content: &str) -> AppResult<Self> {
+        serde_json::from_str(content).map_err(|err| AppError::InvalidArgument(format!("invalid scenario: {}", err)))
+    }
+
+    /// The state before the first step.
+    pub fn initial_state(&self) -> MockState {
+        let champion_ids = self.champions.iter().map(|champion| champion.id).collect::<Vec<_>>();
+        MockState {
+            bannable_champions: champion_ids.clone(),
+            pickable_champions: champion_ids,
+            champions: self.champions.clone(),
+            user_session: self.user_session.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ScenarioStep {
+    /// Wait after the previous step.
+    pub delay_millis: u64,
+    pub phase: Option<GameflowPhase>,
+    /// Champ select phase, `PLANNING`, `BAN_PICK` or `FINALIZATION`, restarting the timer with `time_left_millis`.
+    pub timer_phase: Option<String>,
+    pub time_left_millis: i64,
+    pub actions: Vec<ActionStep>,
+}
+
+
+/// Changes to the action with the given id, other fields are kept.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ActionStep {
+    pub id: i64,
+    pub champion_id: Option<i64>,
+    pub is_in_progress: Option<bool>,
+    pub completed: Option<bool>,
+}
+
+
+fn error_body(http_status: u16, error_code: &str, message: &str) -> serde_json::Value {
+    serde_json::json!({
+        "errorCode": error_code,
+        "httpStatus": http_status,
+        "message": message,
+    })
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        204 => "No Content",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn plays_the_default_scenario() {
+        let scenario = Scenario::parse(DEFAULT_SCENARIO).unwrap();
+        let mock = MockLcu::start(0, scenario.initial_state()).await.unwrap();
+        let mut events = mock.events.subscribe();
+        // without the delays of `play`
+        for step in &scenario.steps {
+            mock.apply_step(step, &scenario.session);
+        }
+        let events = std::iter::from_fn(|| events.try_recv().ok()).collect::<Vec<_>>();
+
+        let phases = events
+            .iter()
+            .filter(|event| event.uri == GAMEFLOW_PHASE_ENDPOINT)
+            .map(|event| serde_json::from_value::<GameflowPhase>(event.data.clone()).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(phases, [
+            GameflowPhase::Lobby,
+            GameflowPhase::Matchmaking,
+            GameflowPhase::ChampSelect,
+            GameflowPhase::GameStart,
+            GameflowPhase::InProgress,
+        ]);
+
+        let session_events = events
+            .iter()
+            .filter(|event| event.uri == CHAMP_SELECT_SESSION_ENDPOINT)
+            .collect::<Vec<_>>();
+        let event_types = session_events.iter().map(|event| event.event_type.as_str()).collect::<Vec<_>>();
+        assert_eq!(event_types.first(), Some(&"Create"));
+        assert_eq!(event_types.last(), Some(&"Delete"));
+        assert!(event_types[1..event_types.len() - 1].iter().all(|&event_type| event_type == "Update"));
+        let mut timer_phases = session_events
+            .iter()
+            .filter_map(|event| serde_json::from_value::<ChampSelectSession>(event.data.clone()).ok())
+            .map(|session| session.timer.phase)
+            .collect::<Vec<_>>();
+        timer_phases.dedup();
+        assert_eq!(timer_phases, ["PLANNING", "BAN_PICK", "FINALIZATION"]);
+        assert_eq!(mock.state().session, None);
+    }
+}
\ No newline at end of file
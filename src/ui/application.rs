@@ -1,23 +1,154 @@
 use std::path::PathBuf;
+use std::sync::Arc;
 
-use iced::{Application, executor, Length};
+use iced::{Application, executor, Length, Subscription};
 use iced::{Command, Element, Theme};
-use iced::widget::{Column, Row, text, text_input};
+use iced::widget::{Column, pick_list, Row, text, text_input, toggler};
 use iced::widget::container;
 use iced_box::icon::material::{load_material_font, Material};
+use tokio::sync::{mpsc, Mutex};
 
-use crate::client::{ban_ahri, check_riot_path};
+use crate::client::{ActionMode, ban_ahri_thread, BanOutcome, ban_champion_list, ban_with_client, check_riot_path, execute_ban, set_accept_invalid_certs, stop_ban_ahri_thread};
+use crate::client::champ_select_session::SkipReason;
+use crate::client::champion_catalog::ChampionCatalog;
+use crate::client::discovery::{discover_riot_paths, RiotPathCandidate};
+use crate::client::gameflow::GameflowPhase;
+use crate::client::ready_check::ready_check_thread;
+use crate::client::pick::{pick_thread, Position};
+use crate::config::{Config, format_champion_list, WindowConfig, parse_champion_list, parse_optional_champion_list};
 use crate::ui::message::Message;
+use crate::ui::state::{ConnectedState, watch_champ_select, watch_gameflow, watch_lockfile, watch_window_size};
 use crate::ui::widget::{custom_button, gif, icons_builder};
 use crate::ui::widget::custom_button::custom_button;
-use crate::wait_n_millis;
+use crate::{AppError, ChampionId, wait_n_millis};
+
+/// A resize emits many events, the window size is saved once it stopped changing for this long.
+const WINDOW_SAVE_DELAY_MILLIS: u64 = 1000;
 
 pub struct MainApp {
     is_banning_ahri: bool,
     is_path_valid: bool,
-    riot_path: String,
+    riot_path_candidates: Vec<RiotPathCandidate>,
     show_ahri_gif: bool,
     frames: Option<gif::Frames>,
+    config: Config,
+    catalog: ChampionCatalog,
+    champion_list: String,
+    is_champion_list_valid: bool,
+    is_auto_ban_armed: bool,
+    ban_ahri_sender: Option<Arc<Mutex<mpsc::Sender<bool>>>>,
+    ready_check_sender: Option<Arc<Mutex<mpsc::Sender<bool>>>>,
+    pick_sender: Option<Arc<Mutex<mpsc::Sender<bool>>>>,
+    pick_position: Position,
+    pick_list: String,
+    is_pick_list_valid: bool,
+    connected_state: Option<ConnectedState>,
+    gameflow_phase: GameflowPhase,
+    last_error: Option<AppError>,
+    status: Option<String>,
+    window_resize_count: u64,
+}
+
+
+impl MainApp {
+    fn arm_auto_ban(&mut self) -> Command<Message> {
+        let (sender, receiver) = mpsc::channel(1);
+        self.ban_ahri_sender = Some(Arc::new(Mutex::new(sender)));
+        Command::perform(
+            ban_ahri_thread(self.config.riot_path.clone(), self.config.ban.champions.clone(), self.config.ban_timing(), receiver),
+            Message::AutoBanFinished,
+        )
+    }
+
+    /// Restarts the ban thread, if armed, so it uses the current path, champions and timing.
+    fn rearm_auto_ban(&mut self) -> Command<Message> {
+        match self.ban_ahri_sender.take() {
+            Some(sender) => Command::batch(vec![
+                Command::perform(stop_ban_ahri_thread(sender), Message::AutoBanDisarmed),
+                self.arm_auto_ban(),
+            ]),
+            None => Command::none(),
+        }
+    }
+
+    fn arm_auto_accept(&mut self) -> Command<Message> {
+        let (sender, receiver) = mpsc::channel(1);
+        self.ready_check_sender = Some(Arc::new(Mutex::new(sender)));
+        Command::perform(
+            ready_check_thread(self.config.riot_path.clone(), self.config.ready_check_options(), receiver),
+            Message::AutoAcceptFinished,
+        )
+    }
+
+    /// Restarts the ready check thread, if armed, so it uses the current path.
+    fn rearm_auto_accept(&mut self) -> Command<Message> {
+        match self.ready_check_sender.take() {
+            Some(sender) => Command::batch(vec![
+                Command::perform(stop_ban_ahri_thread(sender), Message::AutoAcceptDisarmed),
+                self.arm_auto_accept(),
+            ]),
+            None => Command::none(),
+        }
+    }
+
+    fn arm_auto_pick(&mut self) -> Command<Message> {
+        let (sender, receiver) = mpsc::channel(1);
+        self.pick_sender = Some(Arc::new(Mutex::new(sender)));
+        Command::perform(
+            pick_thread(self.config.riot_path.clone(), self.config.pick.preferences.clone(), receiver),
+            Message::AutoPickFinished,
+        )
+    }
+
+    /// Restarts the pick thread, if armed, so it uses the current path and preferences.
+    fn rearm_auto_pick(&mut self) -> Command<Message> {
+        match self.pick_sender.take() {
+            Some(sender) => Command::batch(vec![
+                Command::perform(stop_ban_ahri_thread(sender), Message::AutoPickDisarmed),
+                self.arm_auto_pick(),
+            ]),
+            None => Command::none(),
+        }
+    }
+
+    fn format_pick_list(&self) -> String {
+        let champions = self.config.pick.preferences.get(&self.pick_position).cloned().unwrap_or_default();
+        format_champion_list(&champions, &self.catalog)
+    }
+
+    /// `"Skipped Ahri (already banned), Lux (not bannable)"`, `None` when nothing was skipped.
+    fn format_skipped(&self, skipped: &[(ChampionId, SkipReason)]) -> Option<String> {
+        if skipped.is_empty() {
+            return None;
+        }
+        let skipped = skipped
+            .iter()
+            .map(|(champion_id, reason)| format!("{} ({})", self.catalog.name(*champion_id), reason))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Some(format!("Skipped {}", skipped))
+    }
+
+    /// The ban as confirmed by the client, followed by the skipped champions.
+    fn format_ban(&self, outcome: &BanOutcome) -> String {
+        let banned = format!("Banned {}", self.catalog.name(outcome.champion_id));
+        match self.format_skipped(&outcome.skipped) {
+            Some(skipped) => format!("{}. {}", banned, skipped),
+            None => banned,
+        }
+    }
+
+    fn format_error(&self, err: &AppError) -> String {
+        let message = match err {
+            AppError::NoChampionToBan(skipped) => format!("No champion left to ban. {}.", self.format_skipped(skipped).unwrap_or_default()),
+            AppError::NoChampionToPick(skipped) => format!("No champion left to pick. {}.", self.format_skipped(skipped).unwrap_or_default()),
+            err => err.to_string(),
+        };
+        match err.suggestion() {
+            Some(suggestion) => format!("{} {}", message, suggestion),
+            None => message,
+        }
+    }
 }
 
 
@@ -29,17 +160,61 @@ impl Application for MainApp {
 
 
     fn new(_flags: Self::Flags) -> (Self, Command<Message>) {
-        let riot_path = "C:\\Riot Games".to_string();
-        let is_path_valid = check_riot_path(riot_path.clone());
-        (Self {
+        let config = Config::load();
+        let is_path_valid = check_riot_path(config.riot_path.clone());
+        set_accept_invalid_certs(config.accept_invalid_certs);
+        let catalog = ChampionCatalog::load_cache(ChampionCatalog::cache_path()).unwrap_or_default();
+        let mut app = Self {
             is_banning_ahri: false,
             is_path_valid,
-            riot_path,
+            riot_path_candidates: Vec::new(),
             show_ahri_gif: false,
             frames: None,
-        }, Command::batch(vec![
+            champion_list: format_champion_list(&config.ban.champions, &catalog),
+            catalog,
+            is_champion_list_valid: true,
+            config,
+            is_auto_ban_armed: false,
+            ban_ahri_sender: None,
+            ready_check_sender: None,
+            pick_sender: None,
+            pick_position: Position::Any,
+            pick_list: String::new(),
+            is_pick_list_valid: true,
+            connected_state: None,
+            gameflow_phase: GameflowPhase::None,
+            last_error: None,
+            status: None,
+            window_resize_count: 0,
+        };
+        app.pick_list = app.format_pick_list();
+        let auto_ban = if app.config.ban.auto_ban {
+            app.is_auto_ban_armed = true;
+            app.arm_auto_ban()
+        } else {
+            Command::none()
+        };
+        let auto_accept = if app.config.ready_check.auto_accept {
+            app.arm_auto_accept()
+        } else {
+            Command::none()
+        };
+        let auto_pick = if app.config.pick.auto_pick {
+            app.arm_auto_pick()
+        } else {
+            Command::none()
+        };
+        (app, Command::batch(vec![
             load_material_font().map(Message::FontLoaded),
             gif::Frames::load_from_path(PathBuf::from("assets").join("ahri_by.gif")).map(Message::GifLoaded),
+            // walks the drives and Wine prefixes, kept off the async workers
+            Command::perform(
+                async { tokio::task::spawn_blocking(discover_riot_paths).await.unwrap_or_default() },
+                Message::RiotPathsDiscovered,
+            ),
+            auto_ban,
+            auto_accept,
+            auto_pick,
         ]))
     }
 
@@ -50,30 +225,314 @@ impl Application for MainApp {
 
     fn update(&mut self, message: Message) -> Command<Message> {
         match message {
-            Message::BanAhri => {
-                if self.is_path_valid {
-
-                    self.is_banning_ahri = true;
-                    Command::perform(ban_ahri(self.riot_path.clone()), Message::AhriBanned)
-                } else {
-                    Command::none()
+            Message::BanAhri if self.is_path_valid => {
+                self.is_banning_ahri = true;
+                self.last_error = None;
+                self.status = None;
+                let champions = self.config.ban.champions.clone();
+                match &self.connected_state {
+                    // the ban was resolved from the last session update, a single request is left
+                    Some(ConnectedState { lol_client, prepared_ban: Some(prepared_ban), .. }) => {
+                        let lol_client = lol_client.clone();
+                        let prepared_ban = prepared_ban.clone();
+                        Command::perform(async move { execute_ban(&lol_client, prepared_ban).await }, Message::AhriBanned)
+                    }
+                    Some(ConnectedState { lol_client, .. }) => {
+                        let lol_client = lol_client.clone();
+                        Command::perform(async move { ban_with_client(&lol_client, &champions).await }, Message::AhriBanned)
+                    }
+                    None => {
+                        let riot_path = self.config.riot_path.clone();
+                        Command::perform(async move { ban_champion_list(riot_path, &champions).await }, Message::AhriBanned)
+                    }
                 }
             }
             Message::AhriBanned(result) => {
                 self.is_banning_ahri = false;
 
-                if let Err(err) = result {
-                    println!("Failed to ban Ahri: {:?}", err);
+                match result {
+                    Ok(outcome) if outcome.mode == ActionMode::Hover => {
+                        let hovering = format!("Hovering {} until the ban phase starts", self.catalog.name(outcome.champion_id));
+                        self.status = Some(match self.format_skipped(&outcome.skipped) {
+                            Some(skipped) => format!("{}. {}", hovering, skipped),
+                            None => hovering,
+                        });
+                        Command::none()
+                    }
+                    Ok(outcome) => {
+                        println!("Banned {}", self.catalog.name(outcome.champion_id));
+                        self.status = Some(self.format_ban(&outcome));
+                        self.show_ahri_gif = true;
+                        Command::perform(wait_n_millis(1400), |_| Message::StopShowAhriGif)
+                    }
+                    Err(err) => {
+                        println!("Failed to ban: {:?}", err);
+                        self.last_error = Some(err);
+                        Command::none()
+                    }
+                }
+            }
+            Message::AutoBanToggled(is_armed) => {
+                self.is_auto_ban_armed = is_armed;
+                self.config.ban.auto_ban = is_armed;
+                let save = Command::perform(self.config.clone().save(), Message::ConfigSaved);
+                let thread = if is_armed {
+                    self.arm_auto_ban()
+                } else if let Some(sender) = self.ban_ahri_sender.take() {
+                    Command::perform(stop_ban_ahri_thread(sender), Message::AutoBanDisarmed)
+                } else {
                     Command::none()
+                };
+                Command::batch(vec![save, thread])
+            }
+            Message::LateBanToggled(late_ban) => {
+                self.config.ban.late = late_ban;
+                Command::batch(vec![
+                    Command::perform(self.config.clone().save(), Message::ConfigSaved),
+                    self.rearm_auto_ban(),
+                ])
+            }
+            Message::AutoAcceptToggled(auto_accept) => {
+                self.config.ready_check.auto_accept = auto_accept;
+                let save = Command::perform(self.config.clone().save(), Message::ConfigSaved);
+                let thread = if auto_accept {
+                    self.arm_auto_accept()
+                } else if let Some(sender) = self.ready_check_sender.take() {
+                    Command::perform(stop_ban_ahri_thread(sender), Message::AutoAcceptDisarmed)
                 } else {
+                    Command::none()
+                };
+                Command::batch(vec![save, thread])
+            }
+            Message::AutoPickToggled(auto_pick) => {
+                self.config.pick.auto_pick = auto_pick;
+                let save = Command::perform(self.config.clone().save(), Message::ConfigSaved);
+                let thread = if auto_pick {
+                    self.arm_auto_pick()
+                } else if let Some(sender) = self.pick_sender.take() {
+                    Command::perform(stop_ban_ahri_thread(sender), Message::AutoPickDisarmed)
+                } else {
+                    Command::none()
+                };
+                Command::batch(vec![save, thread])
+            }
+            Message::AutoPickDisarmed(result) => {
+                if let Err(err) = result {
+                    println!("Failed to disarm auto pick: {:?}", err);
+                }
+                Command::none()
+            }
+            Message::AutoPickFinished(result) => {
+                if let Err(err) = result {
+                    println!("Auto pick stopped: {:?}", err);
+                    self.last_error = Some(err);
+                }
+                Command::none()
+            }
+            Message::PickPositionSelected(position) => {
+                self.pick_position = position;
+                self.pick_list = self.format_pick_list();
+                self.is_pick_list_valid = true;
+                Command::none()
+            }
+            Message::PickListChanged(pick_list) => {
+                let champions = parse_optional_champion_list(&pick_list, &self.catalog);
+                self.pick_list = pick_list;
+                self.is_pick_list_valid = champions.is_some();
+                match champions {
+                    Some(champions) if self.config.pick.preferences.get(&self.pick_position) != Some(&champions) => {
+                        if champions.is_empty() {
+                            self.config.pick.preferences.remove(&self.pick_position);
+                        } else {
+                            self.config.pick.preferences.insert(self.pick_position, champions);
+                        }
+                        Command::batch(vec![
+                            Command::perform(self.config.clone().save(), Message::ConfigSaved),
+                            self.rearm_auto_pick(),
+                        ])
+                    }
+                    _ => Command::none(),
+                }
+            }
+            Message::AutoAcceptDisarmed(result) => {
+                if let Err(err) = result {
+                    println!("Failed to disarm auto accept: {:?}", err);
+                }
+                Command::none()
+            }
+            Message::AutoAcceptFinished(result) => {
+                if let Err(err) = result {
+                    println!("Auto accept stopped: {:?}", err);
+                    self.last_error = Some(err);
+                }
+                Command::none()
+            }
+            Message::AutoBanDisarmed(result) => {
+                if let Err(err) = result {
+                    println!("Failed to disarm auto ban: {:?}", err);
+                }
+                Command::none()
+            }
+            Message::AutoBanFinished(result) => match result {
+                Ok(Some(outcome)) => {
+                    println!("Auto banned {}", self.catalog.name(outcome.champion_id));
+                    self.status = Some(self.format_ban(&outcome));
                     self.show_ahri_gif = true;
-                    Command::perform(wait_n_millis(1400), |_| Message::StopShowAhriGif)
+                    let show_gif = Command::perform(wait_n_millis(1400), |_| Message::StopShowAhriGif);
+                    if self.is_auto_ban_armed {
+                        Command::batch(vec![show_gif, self.arm_auto_ban()])
+                    } else {
+                        show_gif
+                    }
                 }
-
+                Ok(None) => Command::none(),
+                Err(err) => {
+                    println!("Auto ban stopped: {:?}", err);
+                    self.last_error = Some(err);
+                    self.is_auto_ban_armed = false;
+                    self.ban_ahri_sender = None;
+                    Command::none()
+                }
+            },
+            Message::ChampionListChanged(champion_list) => {
+                let champions = parse_champion_list(&champion_list, &self.catalog);
+                self.champion_list = champion_list;
+                self.is_champion_list_valid = champions.is_some();
+                match champions {
+                    Some(champions) if champions != self.config.ban.champions => {
+                        self.config.ban.champions = champions;
+                        if let Some(connected_state) = &mut self.connected_state {
+                            connected_state.prepare_ban(&self.config.ban.champions);
+                        }
+                        Command::batch(vec![
+                            Command::perform(self.config.clone().save(), Message::ConfigSaved),
+                            self.rearm_auto_ban(),
+                        ])
+                    }
+                    _ => Command::none(),
+                }
+            }
+            Message::ConfigSaved(result) => {
+                if let Err(err) = result {
+                    println!("Failed to save config: {:?}", err);
+                }
+                Command::none()
             }
             Message::RiotPathChanged(path) => {
-                self.riot_path = path;
-                self.is_path_valid = check_riot_path(self.riot_path.clone());
+                self.config.riot_path = path;
+                self.is_path_valid = check_riot_path(self.config.riot_path.clone());
+                self.connected_state = None;
+                self.gameflow_phase = GameflowPhase::None;
+                // the armed threads look for the client under the path they were started with
+                Command::batch(vec![
+                    Command::perform(self.config.clone().save(), Message::ConfigSaved),
+                    self.rearm_auto_ban(),
+                    self.rearm_auto_accept(),
+                    self.rearm_auto_pick(),
+                ])
+            }
+            Message::RiotPathsDiscovered(candidates) => {
+                println!("Found Riot installations: {:?}", candidates);
+                self.riot_path_candidates = candidates;
+                // the saved path is kept while it is valid
+                match self.riot_path_candidates.first() {
+                    Some(candidate) if !self.is_path_valid => self.update(Message::RiotPathChanged(candidate.path.clone())),
+                    _ => Command::none(),
+                }
+            }
+            Message::RiotPathSelected(candidate) => self.update(Message::RiotPathChanged(candidate.path)),
+            Message::ThemeSelected(theme) => {
+                self.config.theme = theme.to_string();
+                Command::perform(self.config.clone().save(), Message::ConfigSaved)
+            }
+            Message::WindowResized(width, height) => {
+                self.config.window = WindowConfig { width, height };
+                self.window_resize_count += 1;
+                let window_resize_count = self.window_resize_count;
+                Command::perform(wait_n_millis(WINDOW_SAVE_DELAY_MILLIS), move |_| Message::WindowResizeSettled(window_resize_count))
+            }
+            Message::WindowResizeSettled(window_resize_count) if window_resize_count == self.window_resize_count => {
+                Command::perform(self.config.clone().save(), Message::ConfigSaved)
+            }
+            Message::WindowResizeSettled(_) => Command::none(),
+            Message::Connected(connected_state) => {
+                println!("Connected to client on port {}", connected_state.lockfile.port);
+                let lol_client = connected_state.lol_client.clone();
+                self.connected_state = Some(*connected_state);
+                let warm_up_client = lol_client.clone();
+                Command::batch(vec![
+                    Command::perform(async move { warm_up_client.warm_up().await }, Message::ClientWarmedUp),
+                    Command::perform(ChampionCatalog::refresh(lol_client), Message::CatalogLoaded),
+                ])
+            }
+            Message::ClientWarmedUp(result) => {
+                if let Err(err) = result {
+                    println!("Failed to warm up the client connection: {:?}", err);
+                }
+                Command::none()
+            }
+            Message::ChampSelectChanged(session) => {
+                let Some(connected_state) = &mut self.connected_state else {
+                    return Command::none();
+                };
+                let game_id = session.game_id;
+                let has_bannable_champions = connected_state.bannable_champions
+                    .as_ref()
+                    .is_some_and(|(bannable_game_id, _)| *bannable_game_id == game_id);
+                connected_state.champ_select_session = Some(*session);
+                connected_state.prepare_ban(&self.config.ban.champions);
+                if has_bannable_champions {
+                    return Command::none();
+                }
+                let lol_client = connected_state.lol_client.clone();
+                Command::perform(
+                    async move { lol_client.get_bannable_champion_ids().await.map(|champion_ids| (game_id, champion_ids)) },
+                    Message::BannableChampionsLoaded,
+                )
+            }
+            Message::BannableChampionsLoaded(result) => {
+                match (result, &mut self.connected_state) {
+                    (Ok(bannable_champions), Some(connected_state)) => {
+                        connected_state.bannable_champions = Some(bannable_champions);
+                        connected_state.prepare_ban(&self.config.ban.champions);
+                    }
+                    (Err(err), _) => println!("Failed to load bannable champions: {:?}", err),
+                    _ => {}
+                }
+                Command::none()
+            }
+            Message::CatalogLoaded(result) => {
+                match result {
+                    Ok(catalog) => {
+                        self.catalog = catalog;
+                        if parse_champion_list(&self.champion_list, &self.catalog).as_ref() == Some(&self.config.ban.champions) {
+                            self.champion_list = format_champion_list(&self.config.ban.champions, &self.catalog);
+                        }
+                        if self.is_pick_list_valid {
+                            self.pick_list = self.format_pick_list();
+                        }
+                        self.update(Message::ChampionListChanged(self.champion_list.clone()))
+                    }
+                    Err(err) => {
+                        println!("Failed to load champions: {:?}", err);
+                        Command::none()
+                    }
+                }
+            }
+            Message::Disconnected => {
+                self.connected_state = None;
+                self.gameflow_phase = GameflowPhase::None;
+                Command::none()
+            }
+            Message::GameflowChanged(transition) => {
+                println!("Gameflow phase {} -> {}", transition.from, transition.to);
+                self.gameflow_phase = transition.to;
+                if transition.to == GameflowPhase::ChampSelect {
+                    self.last_error = None;
+                    self.status = None;
+                } else if let Some(connected_state) = &mut self.connected_state {
+                    connected_state.clear_champ_select();
+                }
                 Command::none()
             }
             Message::GifLoaded(frames) => {
@@ -95,16 +554,91 @@ impl Application for MainApp {
                     Row::new()
                         .push(text("Riot Path:"))
                         .push(
-                            text_input("Riot Path", self.riot_path.as_ref())
+                            text_input("Riot Path", self.config.riot_path.as_ref())
                                 .on_input(Message::RiotPathChanged)
                         )
-                        .push(if self.is_path_valid {
+                        .push_maybe((!self.riot_path_candidates.is_empty()).then(|| {
+                            let selected = self.riot_path_candidates
+                                .iter()
+                                .find(|candidate| candidate.path == self.config.riot_path)
+                                .cloned();
+                            pick_list(self.riot_path_candidates.clone(), selected, Message::RiotPathSelected)
+                                .placeholder("Detected")
+                                .width(100)
+                        }))
+                        .push(if !self.is_path_valid {
+                            icons_builder(Material::Close).size(20).build()
+                        } else if self.connected_state.is_some() {
+                            icons_builder(Material::CheckCircle).size(20).build()
+                        } else {
+                            icons_builder(Material::LinkOff).size(20).build()
+                        })
+                        .spacing(10)
+                )
+                .push(
+                    Row::new()
+                        .push(text("Champions:"))
+                        .push(
+                            text_input("Ahri, Yasuo", self.champion_list.as_ref())
+                                .on_input(Message::ChampionListChanged)
+                        )
+                        .push(if self.is_champion_list_valid {
+                            icons_builder(Material::CheckCircle).size(20).build()
+                        } else {
+                            icons_builder(Material::Close).size(20).build()
+                        })
+                        .spacing(10)
+                )
+                .push(
+                    Row::new()
+                        .push(pick_list(&Position::ALL[..], Some(self.pick_position), Message::PickPositionSelected))
+                        .push(
+                            text_input("Picks for this role", self.pick_list.as_ref())
+                                .on_input(Message::PickListChanged)
+                        )
+                        .push(if self.is_pick_list_valid {
                             icons_builder(Material::CheckCircle).size(20).build()
                         } else {
                             icons_builder(Material::Close).size(20).build()
                         })
                         .spacing(10)
                 )
+                .push(
+                    Row::new()
+                        .push(toggler(
+                            "Auto ban on my turn".to_string(),
+                            self.is_auto_ban_armed,
+                            Message::AutoBanToggled,
+                        ))
+                        .push(toggler(
+                            "Ban late".to_string(),
+                            self.config.ban.late,
+                            Message::LateBanToggled,
+                        ))
+                        .spacing(20)
+                )
+                .push(
+                    Row::new()
+                        .push(toggler(
+                            "Auto accept ready check".to_string(),
+                            self.config.ready_check.auto_accept,
+                            Message::AutoAcceptToggled,
+                        ))
+                        .push(toggler(
+                            "Auto pick".to_string(),
+                            self.config.pick.auto_pick,
+                            Message::AutoPickToggled,
+                        ))
+                        .spacing(20)
+                )
+                .push(
+                    Row::new()
+                        .push(text(format!("Client: {}", self.gameflow_phase)).width(Length::Fill))
+                        .push(pick_list(Theme::ALL, Some(self.theme()), Message::ThemeSelected))
+                        .spacing(10)
+                )
+                .push_maybe(self.status.as_ref().map(|status| text(status).size(14)))
+                .push_maybe(self.last_error.as_ref().map(|err| text(self.format_error(err)).size(14)))
                 .push(
                     if self.show_ahri_gif {
                         container(gif(self.frames.as_ref().unwrap()))
@@ -113,10 +647,10 @@ impl Application for MainApp {
                             .width(Length::Fill)
                             .height(Length::Fill)
                     } else {
-                        container(custom_button("Ban Ahri")
+                        container(custom_button("Ban")
                             .style(custom_button::primary)
                             .padding([30, 125])
-                            .on_press(Message::BanAhri)
+                            .on_press_maybe((self.gameflow_phase == GameflowPhase::ChampSelect).then_some(Message::BanAhri))
                             .width(Length::Fill)
                             .height(Length::Fill))
                     }
@@ -136,8 +670,28 @@ impl Application for MainApp {
             .into()
     }
 
+    fn subscription(&self) -> Subscription<Message> {
+        if !self.is_path_valid {
+            return watch_window_size();
+        }
+        let gameflow = self.connected_state
+            .as_ref()
+            .map(|connected_state| watch_gameflow(connected_state.lol_client.clone()))
+            .unwrap_or_else(Subscription::none);
+        let champ_select = self.connected_state
+            .as_ref()
+            .filter(|_| self.gameflow_phase == GameflowPhase::ChampSelect)
+            .map(|connected_state| watch_champ_select(connected_state.lol_client.clone()))
+            .unwrap_or_else(Subscription::none);
+        Subscription::batch(vec![watch_lockfile(self.config.riot_path.clone()), gameflow, champ_select, watch_window_size()])
+    }
+
     fn theme(&self) -> Theme {
-        Theme::Dark
+        Theme::ALL
+            .iter()
+            .find(|theme| theme.to_string() == self.config.theme)
+            .cloned()
+            .unwrap_or(Theme::Dark)
     }
 }
 
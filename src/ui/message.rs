@@ -1,16 +1,51 @@
+use iced::Theme;
 use iced_box::icon::LoadingResult;
 
-use crate::AppResult;
+use crate::{AppResult, ChampionId};
+use crate::client::BanOutcome;
+use crate::client::champ_select_session::ChampSelectSession;
+use crate::client::champion_catalog::ChampionCatalog;
+use crate::client::discovery::RiotPathCandidate;
+use crate::client::gameflow::PhaseTransition;
+use crate::client::pick::Position;
+use crate::ui::state::ConnectedState;
 use crate::ui::widget::gif;
 
 #[derive(Debug, Clone)]
 pub enum Message {
     FontLoaded(LoadingResult),
     BanAhri,
-    AhriBanned(AppResult<()>),
+    AhriBanned(AppResult<BanOutcome>),
     StopShowAhriGif,
     RiotPathChanged(String),
+    RiotPathsDiscovered(Vec<RiotPathCandidate>),
+    RiotPathSelected(RiotPathCandidate),
     GifLoaded(Result<gif::Frames, gif::Error>),
+    ChampionListChanged(String),
+    ConfigSaved(AppResult<()>),
+    AutoBanToggled(bool),
+    LateBanToggled(bool),
+    AutoBanDisarmed(AppResult<()>),
+    AutoAcceptToggled(bool),
+    AutoAcceptDisarmed(AppResult<()>),
+    AutoAcceptFinished(AppResult<()>),
+    AutoPickToggled(bool),
+    AutoPickDisarmed(AppResult<()>),
+    AutoPickFinished(AppResult<()>),
+    PickPositionSelected(Position),
+    PickListChanged(String),
+    AutoBanFinished(AppResult<Option<BanOutcome>>),
+    Connected(Box<ConnectedState>),
+    Disconnected,
+    GameflowChanged(PhaseTransition),
+    ChampSelectChanged(Box<ChampSelectSession>),
+    BannableChampionsLoaded(AppResult<(i64, Vec<ChampionId>)>),
+    ClientWarmedUp(AppResult<()>),
+    CatalogLoaded(AppResult<ChampionCatalog>),
+    ThemeSelected(Theme),
+    WindowResized(f32, f32),
+    /// No resize followed the one with this number, the window size can be saved.
+    WindowResizeSettled(u64),
 }
 
 
@@ -18,6 +53,7 @@ pub enum Message {
 
 
 
+#[allow(unused_macros)]
 macro_rules! impl_from_for_message {
     ($source_type:ty => $enum_variant:ident) => {
         impl From<$source_type> for Message {
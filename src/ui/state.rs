@@ -1,17 +1,129 @@
-use crate::AppResult;
-use crate::client::LolClient;
+use iced::futures::SinkExt;
+use iced::{Event, Subscription, window};
 
+use crate::{AppError, AppResult, ChampionId, wait_n_millis};
+use crate::client::{BAN_AHRI_THREAD_RETRY_MILLIS, find_lockfile, LolClient, prepare_ban, PreparedBan};
+use crate::client::champ_select_session::ChampSelectSession;
+use crate::client::gameflow::GameflowTracker;
+use crate::client::lockfile::Lockfile;
+use crate::client::watcher::{LockfileWatcher, WatcherEvent};
+use crate::client::websocket::{CHAMP_SELECT_SESSION_EVENT, LcuWebSocket};
+use crate::ui::message::Message;
+
+/// The connected client, kept for the whole connection so its pooled connection is reused,
+/// and the ban resolved from the latest champ select session.
 #[derive(Debug, Clone, Default)]
 pub struct ConnectedState {
     pub lol_client: LolClient,
-    pub ban_ahri: bool,
+    pub lockfile: Lockfile,
+    pub champ_select_session: Option<ChampSelectSession>,
+    /// Bannable champions of the game with the given id, they don't change during a champ select.
+    pub bannable_champions: Option<(i64, Vec<ChampionId>)>,
+    pub prepared_ban: Option<PreparedBan>,
+}
+
+impl ConnectedState {
+    pub fn new(lockfile: Lockfile) -> AppResult<Self> {
+        Ok(Self {
+            lol_client: LolClient::from_lockfile(&lockfile)?,
+            lockfile,
+            ..Default::default()
+        })
+    }
+
+    /// Resolves the ban action and champion from the latest session, `None` when it can't be done yet.
+    pub fn prepare_ban(&mut self, champion_ids: &[ChampionId]) {
+        self.prepared_ban = match (&self.champ_select_session, &self.bannable_champions) {
+            (Some(session), Some((game_id, bannable_champions))) if *game_id == session.game_id => {
+                prepare_ban(session, champion_ids, bannable_champions).ok()
+            }
+            _ => None,
+        };
+    }
+
+    pub fn clear_champ_select(&mut self) {
+        self.champ_select_session = None;
+        self.prepared_ban = None;
+    }
 }
 
 pub async fn init_connected_state(riot_path: String) -> AppResult<ConnectedState> {
-    let client = LolClient::new(riot_path)?;
-    Ok(ConnectedState {
-        lol_client: client,
-        ..Default::default()
+    ConnectedState::new(find_lockfile(riot_path).await?)
+}
+
+
+/// Emits [`Message::Connected`] and [`Message::Disconnected`] as the client of `riot_path` starts and stops.
+pub fn watch_lockfile(riot_path: String) -> Subscription<Message> {
+    iced::subscription::channel(riot_path.clone(), 10, |mut output| async move {
+        let mut watcher = LockfileWatcher::new(riot_path);
+        loop {
+            let message = match watcher.next_event().await {
+                WatcherEvent::Connected(lockfile) => match ConnectedState::new(lockfile) {
+                    Ok(connected_state) => Message::Connected(Box::new(connected_state)),
+                    Err(err) => {
+                        println!("Failed to connect to the client: {:?}", err);
+                        Message::Disconnected
+                    }
+                },
+                WatcherEvent::Disconnected => Message::Disconnected,
+            };
+            let _ = output.send(message).await;
+        }
+    })
+}
+
+
+/// Emits [`Message::GameflowChanged`] on each gameflow phase change of the connected client.
+pub fn watch_gameflow(lol_client: LolClient) -> Subscription<Message> {
+    iced::subscription::channel(("gameflow", lol_client.port.clone()), 10, |mut output| async move {
+        let mut tracker = GameflowTracker::default();
+        loop {
+            let result = async {
+                let mut socket = LcuWebSocket::connect(&lol_client).await?;
+                GameflowTracker::subscribe(&mut socket).await?;
+                if let Some(transition) = tracker.sync(&lol_client).await? {
+                    let _ = output.send(Message::GameflowChanged(transition)).await;
+                }
+                loop {
+                    let transition = tracker.next_transition(&mut socket).await?;
+                    let _ = output.send(Message::GameflowChanged(transition)).await;
+                }
+            }.await;
+            if let Err::<(), AppError>(err) = result {
+                println!("Gameflow tracking failed, retrying: {:?}", err);
+            }
+            wait_n_millis(BAN_AHRI_THREAD_RETRY_MILLIS).await;
+        }
     })
 }
 
+
+/// Emits [`Message::ChampSelectChanged`] with the current session, then on each update of it.
+pub fn watch_champ_select(lol_client: LolClient) -> Subscription<Message> {
+    iced::subscription::channel(("champ_select", lol_client.port.clone()), 10, |mut output| async move {
+        loop {
+            let result = async {
+                let mut socket = LcuWebSocket::connect(&lol_client).await?;
+                socket.subscribe(CHAMP_SELECT_SESSION_EVENT).await?;
+                let _ = output.send(Message::ChampSelectChanged(Box::new(lol_client.get_champ_select_session().await?))).await;
+                while let Some(session) = socket.next_champ_select_session().await? {
+                    let _ = output.send(Message::ChampSelectChanged(Box::new(session))).await;
+                }
+                Err(AppError::RiotClientError("Websocket closed".to_string()))
+            }.await;
+            if let Err::<(), AppError>(err) = result {
+                println!("Champ select tracking failed, retrying: {:?}", err);
+            }
+            wait_n_millis(BAN_AHRI_THREAD_RETRY_MILLIS).await;
+        }
+    })
+}
+
+
+/// Emits [`Message::WindowResized`] so the window size can be restored on the next start.
+pub fn watch_window_size() -> Subscription<Message> {
+    iced::event::listen_with(|event, _status| match event {
+        Event::Window(_, window::Event::Resized { width, height }) => Some(Message::WindowResized(width as f32, height as f32)),
+        _ => None,
+    })
+}
\ No newline at end of file
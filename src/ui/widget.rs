@@ -12,7 +12,7 @@ pub mod custom_button;
 
 // from https://github.com/tarkah/iced_gif with clone
 pub mod gif;
-pub fn gif(frames: &gif::Frames) -> Gif {
+pub fn gif(frames: &gif::Frames) -> Gif<'_> {
     Gif::new(frames)
 }
 
@@ -94,6 +94,6 @@ pub fn icons_builder(icon: Material) -> IconBuilder {
 }
 
 
-pub fn button_padded(text: &str) -> CustomButton<Message> {
+pub fn button_padded(text: &str) -> CustomButton<'_, Message> {
     custom_button(text).style(custom_button::secondary).padding([12, 24])
 }
\ No newline at end of file